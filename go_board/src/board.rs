@@ -2,9 +2,13 @@ use std::ops::Range;
 use std::fmt;
 use ::{LinearCoord, Color, PointState, LinearCoordVec, Move, BoardError};
 
+/// 列(x座標)の文字表現です。Iは欠番です。GTPの最大サイズである25路盤まで扱えます。
+static COL_STR: [char; 26] =
+    ['@','A','B','C','D','E','F','G','H','J','K','L','M','N','O','P','Q','R','S','T','U','V','W','X','Y','Z'];
+
 /// Boardの具体的な構造体のfmt::Displayのための関数です。
 /// Boardを実装するtype Tでfmt::Displayを以下のように実装してください。
-/// ```
+/// ```ignore
 /// impl fmt::Display for T {
 ///    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 ///        board_fmt(self, f)
@@ -14,17 +18,15 @@ use ::{LinearCoord, Color, PointState, LinearCoordVec, Move, BoardError};
 pub fn board_fmt<T: Board>(p: &T, f: &mut fmt::Formatter) -> fmt::Result {
     use std::fmt::Display;
 
-    static COL_STR: [char; 20] =
-        ['@','A','B','C','D','E','F','G','H','J','K','L','M','N','O','P','Q','R','S','T'];
     let width_plus_1 = p.get_width() + 1; // inclusive rangeがないので計算しておく
     let mut result = Ok(());
     for row in 1..p.get_height() + 1 {
-        result = result.and(write!(f, " {:<2} ", row));
+        result = result.and(write!(f, " {:<2} ", p.get_height() - row + 1));
         for col in 1..width_plus_1 { // col
             result = result.and(p.get_state(p.xy_to_linear(col as u8, row as u8)).fmt(f));
             result = result.and(write!(f, " "));
         }
-        result = result.and(write!(f, "\n"));
+        result = result.and(writeln!(f));
     }
     result = result.and(write!(f, "    "));
     for col in 1..width_plus_1 {
@@ -51,27 +53,23 @@ enum XyMove {
 /// 1 . . . . . . . . . . . . . . . . . .
 ///   A B C D E F G H J K L M N O P Q R S (Iは欠番)
 /// ```
-fn parse_algebraic(s: &str, height: LinearCoord) -> Result<XyMove, BoardError> {
+fn parse_algebraic(s: &str, width: LinearCoord, height: LinearCoord) -> Result<XyMove, BoardError> {
     let st = s.to_uppercase();
 
     if st == "PASS" {
-        Ok(XyMove::Pass)
-    } else {
-        let mut chars = st.chars();
-        match chars.next() {
-            Some(c) => {
-                chars
-                .as_str()
-                .parse::<u8>()
-                .map(|y| {
-                    let x = c as u8 - '@' as u8 - if c < 'J' { 0 } else { 1 };
-                    XyMove::Point(x, height as u8 - y + 1)
-                })
-                .or(Err(BoardError::InvalidVertex))
-            },
-            None => Err(BoardError::InvalidVertex),
-        }
+        return Ok(XyMove::Pass);
+    }
+    let mut chars = st.chars();
+    let c = match chars.next() {
+        Some(c) if c.is_ascii_uppercase() && c != 'I' => c,
+        _ => return Err(BoardError::InvalidVertex),
+    };
+    let x = c as LinearCoord - '@' as LinearCoord - if c < 'J' { 0 } else { 1 };
+    let y = chars.as_str().parse::<LinearCoord>().or(Err(BoardError::InvalidVertex))?;
+    if x > width || y < 1 || y > height {
+        return Err(BoardError::InvalidVertex);
     }
+    Ok(XyMove::Point(x as u8, (height - y + 1) as u8))
 }
 
 /// 碁盤上の状態を操作するトレイトです。
@@ -139,10 +137,10 @@ pub trait Board : fmt::Display {
         self.all_points().filter(|&pt| self.get_state(pt) == PointState::Empty).collect()
     }
 
-    /// 盤上かチェックします。
+    /// 盤上かチェックします。配列の範囲外の線形座標も盤外とします。
     #[inline]
     fn is_on_board(&self, pt: LinearCoord) -> bool {
-        self.get_state(pt) != PointState::Out
+        self.all_points().contains(&pt) && self.get_state(pt) != PointState::Out
     }
 
     /// Move(線形座標)を代数表現に変換します。
@@ -152,16 +150,16 @@ pub trait Board : fmt::Display {
             Move::Resign   => "resign".to_string(),
             Move::Linear(i) => {
                 let (x, y) = self.linear_to_xy(i);
-                let c = '@' as u8 + x;
-                let c = if c > 'H' as u8 { c + 1 } else { c } as char;
-                format!("{}{}", c, self.get_height() as u8 - y + 1)
+                format!("{}{}", COL_STR[x as usize], self.get_height() as u8 - y + 1)
             }
         }
     }
 
     /// 代数形式の座標文字列をMove(線形座標)に変換します。
+    ///
+    /// 盤外の座標や解釈できない文字列はBoardError::InvalidVertexになります。
     fn algebraic_to_move(&self, s: &str) -> Result<Move, BoardError> {
-        parse_algebraic(s, self.get_height()).map(|xymove| match xymove {
+        parse_algebraic(s, self.get_width(), self.get_height()).map(|xymove| match xymove {
             XyMove::Pass => Move::Pass,
             XyMove::Point(x, y) => Move::Linear(self.xy_to_linear(x, y)),
         })
@@ -1,6 +1,26 @@
+use std::error::Error;
+use std::fmt;
+
 /// 盤上の操作で起こるエラーの種類です。
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BoardError {
     /// 座標変換絡みのエラーです。
     InvalidVertex,
+    /// 盤面図の文字表現が不正です。問題の見つかった行と列(1から始まる)を持ちます。
+    /// 行数や列数が足りない場合は、足りない最初の位置を指します。
+    MalformedDiagram {
+        row: usize,
+        column: usize,
+    },
 }
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BoardError::InvalidVertex => write!(f, "invalid vertex"),
+            BoardError::MalformedDiagram { row, column } => write!(f, "malformed diagram at row {}, column {}", row, column),
+        }
+    }
+}
+
+impl Error for BoardError {}
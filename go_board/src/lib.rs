@@ -28,19 +28,19 @@
 //! use std::fmt;
 //! use go_board::*;
 //!
-//! const BOARD_SIZE: usize = 15;
-//! const OB_SIZE: usize = 1;
-//! const ARRAY_SIZE: usize = array_size!(BOARD_SIZE, OB_SIZE);
+//! const BOARD_SIZE: LinearCoord = 15;
+//! const OB_SIZE: LinearCoord = 1;
+//! const ARRAY_SIZE: usize = array_size!(BOARD_SIZE as usize, OB_SIZE as usize);
 //! struct MyGoban {
 //!     turn: Color,
 //!     state: [PointState; ARRAY_SIZE],
 //! }
 //! impl Board for MyGoban {
-//!     fn get_width(&self) -> usize { BOARD_SIZE }
-//!     fn get_height(&self) -> usize { BOARD_SIZE }
-//!     fn get_ob_size(&self) -> usize { OB_SIZE }
-//!     fn get_state(&self, pt: usize) -> PointState { self.state[pt] }
-//!     fn set_state(&mut self, pt: usize, value: PointState) { self.state[pt] = value; }
+//!     fn get_width(&self) -> LinearCoord { BOARD_SIZE }
+//!     fn get_height(&self) -> LinearCoord { BOARD_SIZE }
+//!     fn get_ob_size(&self) -> LinearCoord { OB_SIZE }
+//!     fn get_state(&self, pt: LinearCoord) -> PointState { self.state[pt as usize] }
+//!     fn set_state(&mut self, pt: LinearCoord, value: PointState) { self.state[pt as usize] = value; }
 //!     fn get_turn(&self) -> Color { self.turn }
 //!     fn set_turn(&mut self, value: Color) { self.turn = value }
 //! }
@@ -65,11 +65,15 @@ pub use marker::*;
 
 /// 盤サイズの配列確保用の定数を生成するマクロです。
 /// $sizeは碁盤のサイズ、$ob_sizeはOBの幅です。
+/// 長方形の碁盤の場合は$width, $height, $ob_sizeの順に与えます。
 #[macro_export]
 macro_rules! array_size {
     ($size:expr, $ob_size:expr) => {
-        ($size + $ob_size * 2) * ($size + $ob_size * 2);
-    }
+        array_size!($size, $size, $ob_size)
+    };
+    ($width:expr, $height:expr, $ob_size:expr) => {
+        ($width + $ob_size * 2) * ($height + $ob_size * 2)
+    };
 }
 
 /// 盤上の交点の座標のタイプです。
@@ -79,6 +83,7 @@ pub type LinearCoord = u16;
 /// 着手を表す列挙型です。
 //  TODO - enum使わずusizeで盤外の値をPass, Resignに割り当てたほうが速い。
 //         enumの読みやすさで、LinearCoord/usizeに最適化される書き方を探す。
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Move {
     /// パスです。
     Pass,
@@ -89,10 +94,11 @@ pub enum Move {
 }
 
 /// 盤上の交点の可変長集合を扱うタイプです。
-pub type LinearCoordVec = ArrayVec<[LinearCoord; 384]>;
+/// 25路盤(625点)まで扱えるサイズにしています。
+pub type LinearCoordVec = ArrayVec<[LinearCoord; 768]>;
 
 /// 手番や石の色の列挙型です。
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum Color {
     Black,
     White,
@@ -154,10 +160,7 @@ impl PointState {
     /// 石か否かを返します。
     #[inline]
     pub fn is_stone(&self) -> bool {
-        match *self {
-            PointState::Black | PointState::White => true,
-            _                                     => false,
-        }
+        matches!(*self, PointState::Black | PointState::White)
     }
 
     /// 文字に対応するPointStateを返します。
@@ -184,8 +187,46 @@ impl fmt::Display for PointState {
 
 #[cfg(test)]
 mod tests {
+    use std::fmt;
     use ::*;
 
+    /// 3路盤のテスト用の盤です。
+    struct TestBoard {
+        states: [PointState; 25],
+    }
+
+    impl fmt::Display for TestBoard {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            board_fmt(self, f)
+        }
+    }
+
+    impl Board for TestBoard {
+        fn get_width(&self) -> LinearCoord { 3 }
+        fn get_height(&self) -> LinearCoord { 3 }
+        fn get_ob_size(&self) -> LinearCoord { 1 }
+        fn get_state(&self, pt: LinearCoord) -> PointState { self.states[pt as usize] }
+        fn set_state(&mut self, pt: LinearCoord, value: PointState) { self.states[pt as usize] = value; }
+        fn get_turn(&self) -> Color { Color::Black }
+        fn set_turn(&mut self, _: Color) {}
+    }
+
+    #[test]
+    fn test_board_fmt() {
+        // 行番号は代数形式の座標と同じく下から数えます。
+        let mut board = TestBoard { states: [PointState::Out; 25] };
+        for y in 1..4 {
+            for x in 1..4 {
+                let pt = board.xy_to_linear(x, y);
+                board.set_state(pt, PointState::Empty);
+            }
+        }
+        let pt = board.xy_to_linear(1, 1);
+        board.set_state(pt, PointState::Black);
+        assert_eq!(board.str_coord(Move::Linear(pt)), "A3");
+        assert_eq!(format!("{}", board), " 3  X . . \n 2  . . . \n 1  . . . \n    A B C \n\n");
+    }
+
     #[test]
     fn test_point_state_size() {
         use std::mem;
@@ -44,6 +44,16 @@ macro_rules! make_marker {
                 }
             }
 
+            /// 世代の値が一周するときは、古いマークと区別できるよう配列も初期化します。
+            #[inline]
+            fn clear(&mut self) {
+                if self.value == i32::MAX {
+                    self.value = 0;
+                    self.marks = [0; $array];
+                }
+                self.value += 1;
+            }
+
             #[inline]
             fn get_value(&self) -> i32 {
                 self.value
@@ -0,0 +1,83 @@
+//! go_ruleの局面に対するモンテカルロ木探索(UCT)を提供します。
+//!
+//! 評価はgo_rule::playoutのプレイアウトで行い、
+//! Rule::scoreの符号で勝ち負けを決めます。
+//!
+//! # 使い方
+//! ```
+//! extern crate rand;
+//! extern crate go_rule;
+//! extern crate go_mcts;
+//!
+//! use go_rule::position::Position9;
+//! use go_mcts::*;
+//!
+//! let position = Position9::new();
+//! let config = Config { max_playouts: 100, ..Config::default() };
+//! let result = search(&position, &config, &mut rand::thread_rng());
+//! assert_eq!(result.playouts, 100);
+//! ```
+
+extern crate rand;
+extern crate go_board;
+extern crate go_rule;
+
+mod search;
+pub use search::*;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use rand::{SeedableRng, XorShiftRng};
+    use go_board::*;
+    use go_rule::rule::*;
+    use go_rule::position::*;
+    use super::*;
+
+    fn rng() -> XorShiftRng {
+        XorShiftRng::from_seed([1, 2, 3, 4])
+    }
+
+    #[test]
+    fn test_budget() {
+        let position = Position9::new();
+        let config = Config { max_playouts: 50, ..Config::default() };
+        let result = search(&position, &config, &mut rng());
+        assert_eq!(result.playouts, 50);
+        assert_eq!(result.children.iter().map(|c| c.visits as usize).sum::<usize>(), 50);
+        assert!(result.children.windows(2).all(|w| w[0].visits >= w[1].visits));
+        assert_eq!(result.best_move, result.children[0].mov);
+
+        let config = Config { max_playouts: usize::MAX, max_time: Some(Duration::from_millis(50)), ..Config::default() };
+        let result = search(&position, &config, &mut rng());
+        assert!(result.playouts > 0 && result.playouts < usize::MAX);
+    }
+
+    #[test]
+    fn test_endgame() {
+        // 駄目(3, 1)を打てば黒の0.5目勝ち、白に打たれれば1.5目負け
+        let mut position = Position5x7::from_string("\
+.X.O.
+XXXOO
+XXOOO
+XXOOO
+.XOO.
+XXOOO
+XXOOO").unwrap();
+        position.set_komi(-3.5);
+        let result = search(&position, &Config { max_playouts: 100, ..Config::default() }, &mut rng());
+        assert_eq!(result.best_move, Move::Linear(position.xy_to_linear(3, 1)));
+        assert_eq!(result.children[0].win_rate, 1.0);
+        assert!(result.children.iter().all(|c| c.mov == result.best_move || c.win_rate == 0.0));
+    }
+
+    #[test]
+    fn test_no_candidates() {
+        let mut position = Position5x7::new();
+        for pt in position.empties() {
+            position.set_state(pt, PointState::Black);
+        }
+        let result = search(&position, &Config { max_playouts: 10, ..Config::default() }, &mut rng());
+        assert_eq!(result.best_move, Move::Pass);
+    }
+}
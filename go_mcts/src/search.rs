@@ -0,0 +1,175 @@
+use std::time::{Duration, Instant};
+use rand::Rng;
+use go_board::*;
+use go_rule::rule::*;
+use go_rule::playout::*;
+
+/// 探索の設定です。
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// プレイアウトの最大回数
+    pub max_playouts: usize,
+    /// 探索時間の上限。Noneなら回数だけで打ち切ります。
+    pub max_time: Option<Duration>,
+    /// UCTの探索項の係数
+    pub exploration: f32,
+    /// プレイアウトの設定。既定ではkeep_sekiを有効にして、プレイアウトでセキを壊しません。
+    pub playout: PlayoutConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_playouts: 1000,
+            max_time: None,
+            exploration: 1.0,
+            playout: PlayoutConfig { keep_seki: true, ..PlayoutConfig::default() },
+        }
+    }
+}
+
+/// ルート局面の子ノード(候補手)の統計です。
+#[derive(Clone, Debug)]
+pub struct ChildStats {
+    /// 候補手
+    pub mov: Move,
+    /// 訪問回数
+    pub visits: u32,
+    /// 候補手を打つ側から見た勝率
+    pub win_rate: f32,
+}
+
+/// 探索の結果です。
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    /// 最善手(訪問回数が最も多い手)
+    pub best_move: Move,
+    /// 候補手の統計。訪問回数の多い順です。
+    pub children: Vec<ChildStats>,
+    /// 実行したプレイアウトの回数
+    pub playouts: usize,
+}
+
+/// 探索木のノードです。
+struct Node {
+    /// このノードに至る着手
+    mov: Move,
+    /// movを打った手番
+    color: Color,
+    /// 親ノードの番号
+    parent: Option<usize>,
+    /// 子ノードの番号
+    children: Vec<usize>,
+    /// まだ展開していない候補手。末尾から展開します。
+    untried: Vec<Move>,
+    /// このノードの局面が終局しているならtrue
+    game_over: bool,
+    /// 訪問回数
+    visits: u32,
+    /// colorから見た勝ち数(ジゴは0.5)
+    wins: f32,
+}
+
+/// 局面positionの手番側の候補手を返します。自分の眼を潰す手は除き、パスを先頭に置きます。
+fn candidates<T: Rule, R: Rng>(position: &T, rng: &mut R) -> Vec<Move> {
+    let turn = position.get_turn().to_pointstate();
+    let mut moves: Vec<Move> = position.empties().iter()
+        .filter(|&&pt| position.is_eye(pt) != turn)
+        .map(|&pt| Move::Linear(pt))
+        .collect();
+    rng.shuffle(&mut moves);
+    moves.insert(0, Move::Pass);
+    moves
+}
+
+/// UCTでpositionの手番側の最善手を探索します。
+pub fn search<T: Rule + Clone, R: Rng>(position: &T, config: &Config, rng: &mut R) -> SearchResult {
+    let start = Instant::now();
+    let mut nodes = vec![Node {
+        mov: Move::Pass,
+        color: position.get_turn().opponent(),
+        parent: None,
+        children: Vec::new(),
+        untried: candidates(position, rng),
+        game_over: position.is_game_over(),
+        visits: 0,
+        wins: 0.0,
+    }];
+    let mut playouts = 0;
+
+    while playouts < config.max_playouts {
+        if let Some(max_time) = config.max_time {
+            if start.elapsed() >= max_time {
+                break;
+            }
+        }
+        let mut pos = position.clone();
+        let mut index = 0;
+
+        // 選択
+        while nodes[index].untried.is_empty() && !nodes[index].children.is_empty() && !nodes[index].game_over {
+            let log_visits = (nodes[index].visits as f32).ln();
+            let exploration = config.exploration;
+            index = *nodes[index].children.iter().max_by(|&&a, &&b| {
+                let ucb = |n: &Node| n.wins / n.visits as f32 + exploration * (log_visits / n.visits as f32).sqrt();
+                ucb(&nodes[a]).partial_cmp(&ucb(&nodes[b])).unwrap()
+            }).unwrap();
+            pos.play(nodes[index].mov).expect("expanded move is legal");
+        }
+
+        // 展開
+        if !nodes[index].game_over {
+            while let Some(mov) = nodes[index].untried.pop() {
+                let color = pos.get_turn();
+                if pos.play(mov).is_ok() {
+                    let child = Node {
+                        mov,
+                        color,
+                        parent: Some(index),
+                        children: Vec::new(),
+                        untried: candidates(&pos, rng),
+                        game_over: pos.is_game_over(),
+                        visits: 0,
+                        wins: 0.0,
+                    };
+                    nodes.push(child);
+                    let child_index = nodes.len() - 1;
+                    nodes[index].children.push(child_index);
+                    index = child_index;
+                    break;
+                }
+            }
+        }
+
+        // シミュレーション。終局していればそのまま数えます。
+        let score = playout(pos, &config.playout, rng).score;
+
+        // 逆伝播
+        let mut current = Some(index);
+        while let Some(i) = current {
+            let node = &mut nodes[i];
+            node.visits += 1;
+            node.wins += if score == 0.0 {
+                0.5
+            } else if (score > 0.0) == (node.color == Color::Black) {
+                1.0
+            } else {
+                0.0
+            };
+            current = node.parent;
+        }
+        playouts += 1;
+    }
+
+    let mut children: Vec<ChildStats> = nodes[0].children.iter().map(|&i| ChildStats {
+        mov: nodes[i].mov,
+        visits: nodes[i].visits,
+        win_rate: nodes[i].wins / nodes[i].visits as f32,
+    }).collect();
+    children.sort_by_key(|c| std::cmp::Reverse(c.visits));
+    SearchResult {
+        best_move: children.first().map_or(Move::Pass, |c| c.mov),
+        children,
+        playouts,
+    }
+}
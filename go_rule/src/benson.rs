@@ -0,0 +1,112 @@
+use go_board::*;
+use rule::*;
+use score::*;
+
+/// Bensonのアルゴリズムで求めた、片方の色の無条件に活きている連と領域です。
+///
+/// 無条件に活きている連は、相手が何手続けて打っても、自分がパスし続けても取られません。
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnconditionalLife {
+    /// 無条件に活きている連。各要素は連の石の線形座標の配列です。
+    pub strings: Vec<Vec<LinearCoord>>,
+    /// 活きている連が所有する領域。各要素は領域の点(空点と相手の石)の線形座標の配列です。
+    /// 領域の中の相手の石は死に石、空点は地とみなせます。
+    pub regions: Vec<Vec<LinearCoord>>,
+}
+
+impl UnconditionalLife {
+    /// 無条件に活きている石と、それが所有する領域の点に印をつけた、線形座標で引く配列を返します。
+    pub fn marks<T: Board + ?Sized>(&self, board: &T) -> Vec<bool> {
+        let mut marks = vec![false; board.all_points().end as usize];
+        for &pt in self.strings.iter().chain(self.regions.iter()).flat_map(|points| points.iter()) {
+            marks[pt as usize] = true;
+        }
+        marks
+    }
+}
+
+/// 局面positionの色colorの連のうち、無条件に活きているものをBensonのアルゴリズムで求めます。
+///
+/// colorの石以外の点がつながった領域のうち、空点がすべて連の呼吸点である領域を、その連の生きた領域と呼びます。
+/// 生きた領域が2つ未満の連を除き、除いた連に接する領域を除くことを、変化がなくなるまで繰り返します。
+/// 残った連が無条件に活きている連で、残った連の生きた領域がその連の所有する領域です。
+pub fn unconditional_life<T: Rule + ?Sized>(position: &T, color: Color) -> UnconditionalLife {
+    let stone = color.to_pointstate();
+    let mut string_ids = vec![None; position.all_points().end as usize];
+    let mut strings = Vec::new();
+    for pt in position.all_points() {
+        if position.get_state(pt) != stone || string_ids[pt as usize].is_some() {
+            continue;
+        }
+        let mut string = GoString::new();
+        position.string_at(pt, &mut string);
+        for &e in &string.points {
+            string_ids[e as usize] = Some(strings.len());
+        }
+        strings.push(string);
+    }
+
+    // colorの石以外の点を空点とみなして領域を求めます。
+    let regions = empty_regions(position, |pt| match position.get_state(pt) {
+        state if state == stone => state,
+        PointState::Out => PointState::Out,
+        _ => PointState::Empty,
+    });
+    // 各領域に接する連と、その領域が生きた領域である連
+    let mut neighbors = Vec::with_capacity(regions.len());
+    let mut vitals = Vec::with_capacity(regions.len());
+    for region in &regions {
+        let mut ids: Vec<usize> = Vec::new();
+        for &pt in &region.points {
+            for &a in &position.adjacencies_at(pt) {
+                if let Some(&Some(id)) = string_ids.get(a as usize) {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+            }
+        }
+        let vital: Vec<usize> = ids.iter()
+            .cloned()
+            .filter(|&id| region.points.iter().all(|&pt| {
+                position.get_state(pt) != PointState::Empty || strings[id].liberties.contains(&pt)
+            }))
+            .collect();
+        neighbors.push(ids);
+        vitals.push(vital);
+    }
+
+    let mut alive_strings = vec![true; strings.len()];
+    let mut alive_regions: Vec<bool> = neighbors.iter().map(|ids| !ids.is_empty()).collect();
+    loop {
+        let mut changed = false;
+        for (id, alive) in alive_strings.iter_mut().enumerate() {
+            if *alive && (0..regions.len()).filter(|&r| alive_regions[r] && vitals[r].contains(&id)).count() < 2 {
+                *alive = false;
+                changed = true;
+            }
+        }
+        for (r, alive) in alive_regions.iter_mut().enumerate() {
+            if *alive && neighbors[r].iter().any(|&id| !alive_strings[id]) {
+                *alive = false;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    UnconditionalLife {
+        strings: strings.iter()
+            .zip(&alive_strings)
+            .filter(|&(_, &alive)| alive)
+            .map(|(string, _)| string.points.to_vec())
+            .collect(),
+        regions: regions.into_iter()
+            .enumerate()
+            .filter(|&(r, _)| alive_regions[r] && !vitals[r].is_empty())
+            .map(|(_, region)| region.points)
+            .collect(),
+    }
+}
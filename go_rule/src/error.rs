@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::fmt;
+use go_board::BoardError;
+
+/// ルールに沿った操作で起こるエラーの種類です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleError {
+    /// 石がすでにある点への着手です。
+    Occupied,
+    /// コウによる着手禁止点への着手です。
+    Ko,
+    /// スーパーコウで禁止された、過去の局面を再現する着手です。
+    Superko,
+    /// 自殺手です。
+    Suicide,
+    /// 盤外への着手です。
+    OffBoard,
+    /// 終局(投了)後の着手です。
+    GameOver,
+    /// 手番ではない色の着手です。
+    WrongTurn,
+    /// 盤上の操作のエラーです。
+    Board(BoardError),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RuleError::Occupied => write!(f, "occupied point"),
+            RuleError::Ko => write!(f, "ko"),
+            RuleError::Superko => write!(f, "superko"),
+            RuleError::Suicide => write!(f, "suicide move"),
+            RuleError::OffBoard => write!(f, "off board"),
+            RuleError::GameOver => write!(f, "game is over"),
+            RuleError::WrongTurn => write!(f, "wrong turn"),
+            RuleError::Board(ref e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for RuleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            RuleError::Board(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<BoardError> for RuleError {
+    fn from(e: BoardError) -> Self {
+        RuleError::Board(e)
+    }
+}
@@ -0,0 +1,176 @@
+use go_board::*;
+use rule::*;
+use score::*;
+
+/// 眼形(連に囲まれた空点と死に石の空間)の形です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EyeShape {
+    /// 1目
+    One,
+    /// 2目
+    Two,
+    /// 直三
+    StraightThree,
+    /// 曲がり三
+    BentThree,
+    /// 直四
+    StraightFour,
+    /// 曲がり四(L字とZ字)
+    BentFour,
+    /// 四目中手(田の字)
+    SquareFour,
+    /// 丁四
+    PyramidFour,
+    /// 刀五
+    BulkyFive,
+    /// 花五
+    CrossedFive,
+    /// 花六
+    RabbitySix,
+    /// 上記以外の形です。7目以上の空間や、つながっていない点の集まりも含みます。
+    ///
+    /// 形を調べないので、眼の数は二眼(Eyes::Two)とみなします。
+    Other,
+}
+
+/// 眼形から作れる眼の数です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Eyes {
+    /// 一眼
+    One,
+    /// 二眼
+    Two,
+    /// 急所に先に打った側によって一眼か二眼かが決まります。
+    Unsettled,
+}
+
+impl EyeShape {
+    /// 眼形を囲む側から見た眼の数を返します。
+    pub fn eyes(&self) -> Eyes {
+        match *self {
+            EyeShape::One | EyeShape::Two | EyeShape::SquareFour => Eyes::One,
+            EyeShape::StraightThree | EyeShape::BentThree | EyeShape::PyramidFour |
+            EyeShape::BulkyFive | EyeShape::CrossedFive | EyeShape::RabbitySix => Eyes::Unsettled,
+            EyeShape::StraightFour | EyeShape::BentFour | EyeShape::Other => Eyes::Two,
+        }
+    }
+
+    /// 中手の形ならtrueを返します。
+    ///
+    /// 取られた連がこの形なら、取った側は急所に置かれて一眼にしかなりません。
+    pub fn is_nakade(&self) -> bool {
+        self.eyes() != Eyes::Two
+    }
+}
+
+/// 眼形の分類の結果です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EyeSpace {
+    /// 形
+    pub shape: EyeShape,
+    /// 急所。急所のない形ならNoneです。死に石が急所にあることもあります。
+    pub vital: Option<LinearCoord>,
+}
+
+impl EyeSpace {
+    /// 眼形を囲む側から見た眼の数を返します。
+    #[inline]
+    pub fn eyes(&self) -> Eyes {
+        self.shape.eyes()
+    }
+}
+
+/// 線形座標pointsの点の集まりの眼形を分類し、急所を求めます。
+///
+/// 盤上の形だけを見るので、隅や辺による違い(隅の曲がり四など)は考慮しません。
+pub fn classify_eye_space<T: Board + ?Sized>(board: &T, points: &[LinearCoord]) -> EyeSpace {
+    let xys: Vec<(i32, i32)> = points.iter()
+        .map(|&pt| {
+            let (x, y) = board.linear_to_xy(pt);
+            (x as i32, y as i32)
+        })
+        .collect();
+    let contains = |x: i32, y: i32| xys.contains(&(x, y));
+    // 空間の中で隣接する点の数
+    let degrees: Vec<usize> = xys.iter()
+        .map(|&(x, y)| [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)].iter().filter(|&&(x, y)| contains(x, y)).count())
+        .collect();
+    let point_of_degree = |degree: usize| degrees.iter().position(|&d| d == degree).map(|i| points[i]);
+    let has_square = xys.iter().any(|&(x, y)| contains(x + 1, y) && contains(x, y + 1) && contains(x + 1, y + 1));
+    let is_straight = xys.iter().all(|&(x, _)| x == xys[0].0) || xys.iter().all(|&(_, y)| y == xys[0].1);
+
+    let shape = if !is_connected(&xys) {
+        EyeShape::Other
+    } else {
+        match points.len() {
+            1 => EyeShape::One,
+            2 => EyeShape::Two,
+            3 if is_straight => EyeShape::StraightThree,
+            3 => EyeShape::BentThree,
+            4 if has_square => EyeShape::SquareFour,
+            4 if point_of_degree(3).is_some() => EyeShape::PyramidFour,
+            4 if is_straight => EyeShape::StraightFour,
+            4 => EyeShape::BentFour,
+            5 if point_of_degree(4).is_some() => EyeShape::CrossedFive,
+            5 if has_square => EyeShape::BulkyFive,
+            6 if has_square && point_of_degree(4).is_some() => EyeShape::RabbitySix,
+            _ => EyeShape::Other,
+        }
+    };
+    let vital = match shape {
+        EyeShape::StraightThree | EyeShape::BentThree => point_of_degree(2),
+        EyeShape::PyramidFour | EyeShape::BulkyFive => point_of_degree(3),
+        EyeShape::CrossedFive | EyeShape::RabbitySix => point_of_degree(4),
+        _ => None,
+    };
+    EyeSpace { shape, vital }
+}
+
+/// xy座標の点の集まりがつながっているならtrueを返します。
+fn is_connected(xys: &[(i32, i32)]) -> bool {
+    if xys.is_empty() {
+        return false;
+    }
+    let mut visited = vec![false; xys.len()];
+    let mut stack = vec![0];
+    visited[0] = true;
+    while let Some(i) = stack.pop() {
+        let (x, y) = xys[i];
+        for (j, &(x2, y2)) in xys.iter().enumerate() {
+            if !visited[j] && (x - x2).abs() + (y - y2).abs() == 1 {
+                visited[j] = true;
+                stack.push(j);
+            }
+        }
+    }
+    visited.iter().all(|&v| v)
+}
+
+/// 眼形の中の死に石とみなす相手の連の呼吸点の最大数です。
+const MAX_DEAD_STONE_LIBERTIES: usize = 2;
+
+/// 局面positionで、片方の色の石だけに囲まれたmax_size目以下の領域をすべて分類します。
+///
+/// 領域は空点と、呼吸点がMAX_DEAD_STONE_LIBERTIES以下の相手の石(死に石)からなります。
+/// 囲んでいる色、領域の点、分類の結果の組を返します。黒が囲む領域が先です。
+pub fn eye_spaces<T: Rule + ?Sized>(position: &T, max_size: usize) -> Vec<(Color, Vec<LinearCoord>, EyeSpace)> {
+    let mut spaces = Vec::new();
+    for &color in &[Color::Black, Color::White] {
+        let dead = color.opponent().to_pointstate();
+        let regions = empty_regions(position, |pt| {
+            let state = position.get_state(pt);
+            if state == dead && position.num_liberties_at(pt) <= MAX_DEAD_STONE_LIBERTIES {
+                PointState::Empty
+            } else {
+                state
+            }
+        });
+        for region in regions {
+            if region.points.len() <= max_size && region.owner() == color.to_pointstate() {
+                let eye_space = classify_eye_space(position, &region.points);
+                spaces.push((color, region.points, eye_space));
+            }
+        }
+    }
+    spaces
+}
@@ -0,0 +1,222 @@
+use go_board::*;
+use rule::*;
+use error::*;
+use ruleset::*;
+
+/// ゲームツリーのノードです。
+struct GameNode {
+    /// このノードに至る着手。ルートノードはNoneです。
+    mov: Option<(Color, Move)>,
+    /// 親ノードの番号
+    parent: Option<usize>,
+    /// 子ノードの番号。最初のものが本譜です。
+    children: Vec<usize>,
+    /// コメント
+    comment: String,
+    /// その他のプロパティ(名前, 値)
+    properties: Vec<(String, String)>,
+}
+
+impl GameNode {
+    fn new(mov: Option<(Color, Move)>, parent: Option<usize>) -> Self {
+        GameNode {
+            mov,
+            parent,
+            children: Vec::new(),
+            comment: String::new(),
+            properties: Vec::new(),
+        }
+    }
+}
+
+/// 局面と変化を含む着手の木を保持する構造体です。
+///
+/// 現在のノードまでの着手のMoveLogを保持し、木の中を前後に移動できます。
+/// ノードはすべて1つの配列に保持し、番号で参照します。番号0がルートノードです。
+pub struct Game<T: Rule> {
+    /// 現在のノードの局面
+    position: T,
+    /// ノードの配列
+    nodes: Vec<GameNode>,
+    /// 現在のノードの番号
+    current: usize,
+    /// ルートノードから現在のノードまでの着手のMoveLog
+    move_logs: Vec<MoveLog>,
+}
+
+impl<T: Rule> Game<T> {
+    /// positionを初期局面とするGameを返します。
+    pub fn new(position: T) -> Self {
+        Game {
+            position,
+            nodes: vec![GameNode::new(None, None)],
+            current: 0,
+            move_logs: Vec::new(),
+        }
+    }
+
+    /// 現在の局面を返します。
+    #[inline]
+    pub fn position(&self) -> &T {
+        &self.position
+    }
+
+    /// コミを設定します。
+    pub fn set_komi(&mut self, value: f32) {
+        self.position.set_komi(value);
+    }
+
+    /// ルールを設定します。
+    pub fn set_rule_set(&mut self, value: RuleSet) {
+        self.position.set_rule_set(value);
+    }
+
+    /// 現在の手数を返します。初期局面では0です。
+    #[inline]
+    pub fn move_number(&self) -> usize {
+        self.move_logs.len()
+    }
+
+    /// 現在のノードの番号を返します。
+    #[inline]
+    pub fn current_node(&self) -> usize {
+        self.current
+    }
+
+    /// 現在のノードに至る着手の手番と着手を返します。初期局面ではNoneです。
+    #[inline]
+    pub fn current_move(&self) -> Option<(Color, Move)> {
+        self.nodes[self.current].mov
+    }
+
+    /// 現在のノードに至る着手のMoveLogの配列を返します。
+    #[inline]
+    pub fn move_logs(&self) -> &[MoveLog] {
+        &self.move_logs
+    }
+
+    /// 手番colorで着手movを打ち、そのノードに移動します。
+    /// 同じ着手の子ノードがあればそこに移動し、なければ新しい変化として子ノードを追加します。
+    pub fn play_as(&mut self, color: Color, mov: Move) -> Result<(), RuleError> {
+        let turn = self.position.get_turn();
+        self.position.set_turn(color);
+        let move_log = match self.position.play(mov) {
+            Ok(move_log) => move_log,
+            Err(e) => {
+                self.position.set_turn(turn);
+                return Err(e);
+            },
+        };
+        self.move_logs.push(move_log);
+        let found = self.nodes[self.current].children.iter()
+            .cloned()
+            .find(|&child| self.nodes[child].mov == Some((color, mov)));
+        self.current = match found {
+            Some(child) => child,
+            None => {
+                let child = self.nodes.len();
+                self.nodes.push(GameNode::new(Some((color, mov)), Some(self.current)));
+                self.nodes[self.current].children.push(child);
+                child
+            },
+        };
+        Ok(())
+    }
+
+    /// 現在の手番で着手movを打ちます。play_asを参照してください。
+    pub fn play(&mut self, mov: Move) -> Result<(), RuleError> {
+        let turn = self.position.get_turn();
+        self.play_as(turn, mov)
+    }
+
+    /// 1手戻ります。初期局面ならfalseを返します。
+    pub fn back(&mut self) -> bool {
+        match self.move_logs.pop() {
+            Some(move_log) => {
+                self.position.undo_play(&move_log);
+                self.current = self.nodes[self.current].parent.expect("non-root node has parent");
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// index番目の子ノードに進みます。子ノードがなければfalseを返します。
+    pub fn forward_to(&mut self, index: usize) -> bool {
+        let child = match self.nodes[self.current].children.get(index) {
+            Some(&child) => child,
+            None => return false,
+        };
+        let (color, mov) = self.nodes[child].mov.expect("child node has move");
+        self.play_as(color, mov).is_ok()
+    }
+
+    /// 本譜(最初の子ノード)に1手進みます。子ノードがなければfalseを返します。
+    pub fn forward(&mut self) -> bool {
+        self.forward_to(0)
+    }
+
+    /// 手数がmove_numberの局面に移動します。
+    /// 戻る場合は現在の手順を、進む場合は本譜をたどります。移動後の手数を返します。
+    pub fn go_to_move(&mut self, move_number: usize) -> usize {
+        while self.move_number() > move_number && self.back() {}
+        while self.move_number() < move_number && self.forward() {}
+        self.move_number()
+    }
+
+    /// 現在のノードの変化(兄弟ノード)の数を返します。現在のノード自身も含みます。
+    pub fn num_variations(&self) -> usize {
+        match self.nodes[self.current].parent {
+            Some(parent) => self.nodes[parent].children.len(),
+            None => 1,
+        }
+    }
+
+    /// 現在のノードが何番目の変化かを返します。本譜なら0です。
+    pub fn variation_index(&self) -> usize {
+        match self.nodes[self.current].parent {
+            Some(parent) => self.nodes[parent].children.iter().position(|&c| c == self.current).unwrap(),
+            None => 0,
+        }
+    }
+
+    /// 現在のノードをindex番目の変化(兄弟ノード)に切り替えます。該当する変化がなければfalseを返します。
+    pub fn switch_variation(&mut self, index: usize) -> bool {
+        if index >= self.num_variations() || index == self.variation_index() {
+            return index < self.num_variations();
+        }
+        self.back();
+        self.forward_to(index)
+    }
+
+    /// 現在のノードの子ノードの数を返します。
+    pub fn num_children(&self) -> usize {
+        self.nodes[self.current].children.len()
+    }
+
+    /// 現在のノードのコメントを返します。
+    pub fn comment(&self) -> &str {
+        &self.nodes[self.current].comment
+    }
+
+    /// 現在のノードのコメントを設定します。
+    pub fn set_comment(&mut self, comment: &str) {
+        self.nodes[self.current].comment = comment.to_string();
+    }
+
+    /// 現在のノードのプロパティnameの値を返します。
+    pub fn get_property(&self, name: &str) -> Option<&str> {
+        self.nodes[self.current].properties.iter()
+            .find(|property| property.0 == name)
+            .map(|property| property.1.as_str())
+    }
+
+    /// 現在のノードのプロパティnameの値を設定します。
+    pub fn set_property(&mut self, name: &str, value: &str) {
+        let properties = &mut self.nodes[self.current].properties;
+        match properties.iter_mut().find(|property| property.0 == name) {
+            Some(property) => property.1 = value.to_string(),
+            None => properties.push((name.to_string(), value.to_string())),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::cmp::min;
+use go_board::*;
+
+/// 置き石の最大数(固定置き石)です。
+pub const MAX_FIXED_HANDICAP: usize = 9;
+
+/// GTPのfixed_handicapの置き石の点を返します。
+///
+/// 9路、13路、19路の盤で2から9個の置き石を星に置きます。順番はGTPの仕様のとおりです。
+/// それ以外の盤サイズや個数ならNoneを返します。
+pub fn fixed_handicap_points<T: Board + ?Sized>(board: &T, num: usize) -> Option<Vec<LinearCoord>> {
+    let size = board.get_width();
+    if size != board.get_height() || !(2..=MAX_FIXED_HANDICAP).contains(&num) {
+        return None;
+    }
+    let edge = match size {
+        9 => 3,
+        13 | 19 => 4,
+        _ => return None,
+    };
+    let low = edge as u8;
+    let high = (size - edge + 1) as u8;
+    let middle = (size / 2 + 1) as u8;
+    // xy座標は左上が原点なので、GTPのD4(左下)は(low, high)です。
+    let mut points = vec![(low, high), (high, low)];
+    if num >= 3 {
+        points.push((low, low));
+    }
+    if num >= 4 {
+        points.push((high, high));
+    }
+    if num >= 6 {
+        points.push((low, middle));
+        points.push((high, middle));
+    }
+    if num >= 8 {
+        points.push((middle, high));
+        points.push((middle, low));
+    }
+    if num % 2 == 1 && num >= 5 {
+        points.push((middle, middle));
+    }
+    Some(points.into_iter().map(|(x, y)| board.xy_to_linear(x, y)).collect())
+}
+
+/// GTPのplace_free_handicapの置き石の点を返します。
+///
+/// 固定置き石があり、その点がすべて空点ならそれを使います。
+/// それ以外は辺から離れた点の中から、すでに選んだ点から最も遠い点を順に選びます。
+/// 盤を埋め尽くさないように、空点の数より1つ少ない数までしか選びません。
+pub fn free_handicap_points<T: Board + ?Sized>(board: &T, num: usize) -> Vec<LinearCoord> {
+    let empties = board.empties();
+    let num = min(num, empties.len().saturating_sub(1));
+    if let Some(points) = fixed_handicap_points(board, num) {
+        if points.iter().all(|pt| empties.contains(pt)) {
+            return points;
+        }
+    }
+
+    let width = board.get_width() as i32;
+    let height = board.get_height() as i32;
+    // 何線か
+    let line = |pt: LinearCoord| {
+        let (x, y) = board.linear_to_xy(pt);
+        let (x, y) = (x as i32, y as i32);
+        min(min(x, width + 1 - x), min(y, height + 1 - y))
+    };
+    let margin = match min(width, height) {
+        s if s >= 13 => 4,
+        s if s >= 7 => 3,
+        _ => 1,
+    };
+    let distance = |a: LinearCoord, b: LinearCoord| {
+        let (ax, ay) = board.linear_to_xy(a);
+        let (bx, by) = board.linear_to_xy(b);
+        let (dx, dy) = (ax as i32 - bx as i32, ay as i32 - by as i32);
+        dx * dx + dy * dy
+    };
+
+    let mut points: Vec<LinearCoord> = Vec::new();
+    while points.len() < num {
+        let candidates: Vec<LinearCoord> = empties.iter()
+            .cloned()
+            .filter(|pt| !points.contains(pt))
+            .collect();
+        let inner: Vec<LinearCoord> = candidates.iter().cloned().filter(|&pt| line(pt) >= margin).collect();
+        let candidates = if inner.is_empty() { candidates } else { inner };
+        // 最初に見つかった最も遠い点を選ぶので、同じ局面なら結果は同じです。
+        let best = candidates.iter().cloned().fold(None, |best: Option<(LinearCoord, i32)>, pt| {
+            let d = points.iter().map(|&p| distance(p, pt)).min().unwrap_or(i32::MAX);
+            match best {
+                Some((_, best_d)) if best_d >= d => best,
+                _ => Some((pt, d)),
+            }
+        });
+        match best {
+            Some((pt, _)) => points.push(pt),
+            None => break,
+        }
+    }
+    points
+}
@@ -0,0 +1,67 @@
+use go_board::Color;
+
+/// コウの扱いを表す列挙型です。
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum KoRule {
+    /// 直前の局面に戻す着手(単純なコウ)だけを禁止します。
+    Simple,
+    /// 過去の盤面と同じ盤面を作る着手を禁止します(Positional Superko)。
+    Positional,
+    /// 過去の盤面と手番の組と同じものを作る着手を禁止します(Situational Superko)。
+    Situational,
+}
+
+/// スーパーコウ判定のための局面の履歴です。
+///
+/// 着手前の盤面のハッシュ値と手番を記録します。現局面は含みません。
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    entries: Vec<(u64, Color)>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        History {
+            entries: Vec::new(),
+        }
+    }
+
+    /// 記録されている局面の数を返します。
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 局面が1つも記録されていないか返します。
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 盤面のハッシュ値hashと次の手番turnの局面を記録します。
+    #[inline]
+    pub fn push(&mut self, hash: u64, turn: Color) {
+        self.entries.push((hash, turn));
+    }
+
+    /// 記録をlen個に切り詰めます。undoで使います。
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+
+    /// 記録をすべて消去します。
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 盤面hashで次の手番がturnの局面が、ko_ruleのもとで過去の局面の繰り返しになるか返します。
+    pub fn is_repetition(&self, ko_rule: KoRule, hash: u64, turn: Color) -> bool {
+        match ko_rule {
+            KoRule::Simple      => false,
+            KoRule::Positional  => self.entries.iter().any(|&(h, _)| h == hash),
+            KoRule::Situational => self.entries.iter().any(|&(h, t)| h == hash && t == turn),
+        }
+    }
+}
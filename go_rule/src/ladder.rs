@@ -0,0 +1,209 @@
+use go_board::*;
+use rule::*;
+
+/// シチョウを読む最大手数です。これを超えると逃げられたとみなします。
+pub const MAX_LADDER_DEPTH: usize = 256;
+
+/// シチョウの読みの結果です。
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ladder {
+    /// 追う側が取れるならtrue
+    pub captured: bool,
+    /// 読み筋。取れるなら取れるまでの手順、逃げられるなら逃げる手順です。黒白交互の着手です。
+    pub moves: Vec<Move>,
+}
+
+/// 線形座標ptの石を含む連のシチョウを読みます。
+///
+/// 呼吸点が1つの連は逃げる側の手番、2つの連は追う側の手番として読みます。
+/// 逃げる側は呼吸点に伸びるか、アタリになった追う側の石を取ります。シチョウアタリ(途中の逃げる側の石)、
+/// 取って逃げる手、盤端は着手を実際に打って調べるので自然に扱えます。
+/// 局面はplayとundo_playで元に戻します。石がない点や呼吸点が3つ以上の連ならNoneを返します。
+pub fn read_ladder<T: Rule>(position: &mut T, pt: LinearCoord) -> Option<Ladder> {
+    if !position.is_on_board(pt) || !position.get_state(pt).is_stone() {
+        return None;
+    }
+    let turn = position.get_turn();
+    let result = match liberties(position, pt).len() {
+        1 => {
+            let (escaped, moves) = defend(position, pt, 0);
+            Some(Ladder { captured: !escaped, moves })
+        },
+        2 => {
+            let (captured, moves) = attack(position, pt, 0);
+            Some(Ladder { captured, moves })
+        },
+        _ => None,
+    };
+    position.set_turn(turn);
+    result
+}
+
+/// 手番の側がシチョウで取れる相手の呼吸点2つの連に対して、シチョウが成功する最初の着手の点を返します。
+/// ニューラルネットワークの入力やプレイアウトの着手の候補に使えます。
+pub fn ladder_captures<T: Rule>(position: &mut T) -> Vec<LinearCoord> {
+    let turn = position.get_turn();
+    let mut result = Vec::new();
+    for pt in string_heads(position, turn.opponent(), 2) {
+        for lib in liberties(position, pt) {
+            if !result.contains(&lib) && atari_succeeds(position, pt, lib, 0).0 {
+                result.push(lib);
+            }
+        }
+    }
+    position.set_turn(turn);
+    result
+}
+
+/// 手番の側のアタリの連が、シチョウから逃げられる着手の点を返します。
+pub fn ladder_escapes<T: Rule>(position: &mut T) -> Vec<LinearCoord> {
+    let turn = position.get_turn();
+    let mut result = Vec::new();
+    for pt in string_heads(position, turn, 1) {
+        for mov in escape_moves(position, pt) {
+            if !result.contains(&mov) && escape_succeeds(position, pt, mov, 0).0 {
+                result.push(mov);
+            }
+        }
+    }
+    position.set_turn(turn);
+    result
+}
+
+/// 色colorの呼吸点がnum_liberties個の連ごとに、石の点を1つずつ返します。
+fn string_heads<T: Rule>(position: &T, color: Color, num_liberties: usize) -> Vec<LinearCoord> {
+    let mut marked = vec![false; position.all_points().end as usize];
+    let mut heads = Vec::new();
+    for pt in position.all_points() {
+        if marked[pt as usize] || position.get_state(pt) != color.to_pointstate() {
+            continue;
+        }
+        let mut string = GoString::new();
+        position.string_at(pt, &mut string);
+        for &e in &string.points {
+            marked[e as usize] = true;
+        }
+        if string.num_liberties() == num_liberties {
+            heads.push(pt);
+        }
+    }
+    heads
+}
+
+/// 線形座標ptの連の呼吸点を返します。
+fn liberties<T: Rule>(position: &T, pt: LinearCoord) -> Vec<LinearCoord> {
+    let mut string = GoString::new();
+    position.string_at(pt, &mut string);
+    string.liberties.to_vec()
+}
+
+/// 呼吸点が1つの線形座標ptの連が逃げる手の候補を返します。
+/// アタリになっている隣の相手の連を取る手と、呼吸点に伸びる手です。
+fn escape_moves<T: Rule>(position: &T, pt: LinearCoord) -> Vec<LinearCoord> {
+    let mut string = GoString::new();
+    position.string_at(pt, &mut string);
+    let opponent = position.get_state(pt).opponent();
+    let mut moves = Vec::new();
+    for &e in &string.points {
+        for &a in &position.adjacencies_at(e) {
+            if position.get_state(a) == opponent {
+                let libs = liberties(position, a);
+                if libs.len() == 1 && !moves.contains(&libs[0]) {
+                    moves.push(libs[0]);
+                }
+            }
+        }
+    }
+    for &lib in &string.liberties {
+        if !moves.contains(&lib) {
+            moves.push(lib);
+        }
+    }
+    moves
+}
+
+/// 呼吸点が1つの線形座標ptの連を逃げる側の手番で読みます。
+/// 逃げられるならtrueと逃げる手順、逃げられないならfalseと取られるまでの手順を返します。
+fn defend<T: Rule>(position: &mut T, pt: LinearCoord, depth: usize) -> (bool, Vec<Move>) {
+    if depth >= MAX_LADDER_DEPTH {
+        return (true, Vec::new());
+    }
+    let mut failure = Vec::new();
+    for mov in escape_moves(position, pt) {
+        let (escaped, moves) = escape_succeeds(position, pt, mov, depth);
+        if escaped {
+            return (true, moves);
+        }
+        if moves.len() >= failure.len() {
+            failure = moves;
+        }
+    }
+    (false, failure)
+}
+
+/// 線形座標ptの連の側が線形座標movに打って逃げられるか読みます。
+fn escape_succeeds<T: Rule>(position: &mut T, pt: LinearCoord, mov: LinearCoord, depth: usize) -> (bool, Vec<Move>) {
+    let color = match position.get_state(pt) {
+        PointState::Black => Color::Black,
+        _ => Color::White,
+    };
+    position.set_turn(color);
+    let move_log = match position.play(Move::Linear(mov)) {
+        Ok(move_log) => move_log,
+        Err(_) => return (false, Vec::new()),
+    };
+    let (escaped, mut moves) = match liberties(position, pt).len() {
+        0 | 1 => (false, Vec::new()),
+        2 => {
+            let (captured, moves) = attack(position, pt, depth + 1);
+            (!captured, moves)
+        },
+        _ => (true, Vec::new()),
+    };
+    position.undo_play(&move_log);
+    moves.insert(0, Move::Linear(mov));
+    (escaped, moves)
+}
+
+/// 呼吸点が2つの線形座標ptの連を追う側の手番で読みます。
+/// 取れるならtrueと取れるまでの手順、取れないならfalseと逃げられる手順を返します。
+fn attack<T: Rule>(position: &mut T, pt: LinearCoord, depth: usize) -> (bool, Vec<Move>) {
+    if depth >= MAX_LADDER_DEPTH {
+        return (false, Vec::new());
+    }
+    let mut failure = Vec::new();
+    for lib in liberties(position, pt) {
+        let (captured, moves) = atari_succeeds(position, pt, lib, depth);
+        if captured {
+            return (true, moves);
+        }
+        if moves.len() >= failure.len() {
+            failure = moves;
+        }
+    }
+    (false, failure)
+}
+
+/// 追う側が線形座標libに打って線形座標ptの連をアタリにし、シチョウで取れるか読みます。
+fn atari_succeeds<T: Rule>(position: &mut T, pt: LinearCoord, lib: LinearCoord, depth: usize) -> (bool, Vec<Move>) {
+    let color = match position.get_state(pt) {
+        PointState::Black => Color::White,
+        _ => Color::Black,
+    };
+    position.set_turn(color);
+    let move_log = match position.play(Move::Linear(lib)) {
+        Ok(move_log) => move_log,
+        Err(_) => return (false, Vec::new()),
+    };
+    let (captured, mut moves) = if !position.get_state(pt).is_stone() {
+        (true, Vec::new())
+    } else if liberties(position, pt).len() == 1 {
+        let (escaped, moves) = defend(position, pt, depth + 1);
+        (!escaped, moves)
+    } else {
+        (false, Vec::new())
+    };
+    position.undo_play(&move_log);
+    moves.insert(0, Move::Linear(lib));
+    (captured, moves)
+}
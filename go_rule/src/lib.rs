@@ -5,59 +5,49 @@ extern crate rand;
 extern crate arrayvec;
 extern crate go_board;
 
+pub mod error;
 pub mod rule;
 pub mod position;
+pub mod history;
+pub mod zobrist;
+pub mod game;
+pub mod playout;
+pub mod result;
+pub mod score;
+pub mod ruleset;
+pub mod handicap;
+pub mod benson;
+pub mod eye;
+pub mod seki;
+pub mod ladder;
+pub mod tactics;
+pub mod semeai;
 
 
 #[cfg(test)]
 mod tests {
     use go_board::*;
     use position::*;
-    use rand::{thread_rng, Rng};
-    use rule::Rule;
+    use rand::{thread_rng, Rng, SeedableRng, XorShiftRng};
+    use rule::*;
+    use history::*;
+    use game::*;
+    use playout::*;
+    use error::*;
+    use result::*;
+    use score::*;
+    use ruleset::*;
+    use handicap::*;
+    use benson::*;
+    use eye::*;
+    use seki::*;
+    use ladder::*;
+    use tactics::*;
+    use semeai::*;
 
-    fn rollout() -> (u32, f32) {
-        let mut rng = thread_rng();
-        let mut game = Position19::new();
-        let mut num_consecutive_passes = 0;
-        let mut num_moves = 0;
-
-        while num_consecutive_passes < 2 {
-            let candidates = game.empties();
-            let mut played = false;
-            if candidates.len() > 0 {
-                let start_index = rng.gen_range(0, candidates.len());
-                let mut i = start_index;
-                loop {
-                    let pt = candidates[i];
-                    if game.is_eye(pt) != game.get_turn().to_pointstate() {
-                        if let Ok(_) = game.play(Move::Linear(pt)) {
-                            played = true;
-                            break;
-                        }
-                    }
-                    i += 1;
-                    if i >= candidates.len() {
-                        i = 0;
-                    }
-                    if i == start_index {
-                        break;
-                    }
-                }
-            }
-            if played {
-                num_consecutive_passes = 0;
-            } else {
-                let _ = game.play(Move::Pass);
-                num_consecutive_passes += 1;
-            }
-            num_moves += 1;
-            if num_moves > 1000 {
-                println!("suspicious game with > 1000 moves");
-                break;
-            }
-        }
-        return (num_moves, game.score());
+    fn rollout<T: Rule + Default>() -> (u32, f32) {
+        let result = playout(T::default(), &PlayoutConfig::default(), &mut thread_rng());
+        (result.num_moves as u32, result.score)
     }
 
     #[test]
@@ -80,14 +70,1084 @@ mod tests {
         assert!(pos.is_eye(point) == PointState::Black);
     }
 
+    #[test]
+    fn test_play_errors() {
+        let mut pos = Position9::from_string("\
+.X.......
+X........
+.........
+.........
+.........
+.........
+.........
+.........
+.........").unwrap();
+        pos.set_turn(Color::White);
+        assert_eq!(pos.play(Move::Linear(pos.xy_to_linear(1, 1))).err(), Some(RuleError::Suicide));
+        assert_eq!(pos.get_turn(), Color::White);
+        assert_eq!(RuleError::Suicide.to_string(), "suicide move");
+        assert_eq!(RuleError::from(BoardError::InvalidVertex), RuleError::Board(BoardError::InvalidVertex));
+    }
+
+    #[test]
+    fn test_occupied_and_off_board() {
+        let mut pos = Position9::new();
+        let pt = pos.xy_to_linear(5, 5);
+        pos.play(Move::Linear(pt)).unwrap();
+        let hash = pos.get_hash();
+        assert_eq!(pos.check_move(Move::Linear(pt)), Err(RuleError::Occupied));
+        assert_eq!(pos.play(Move::Linear(pt)).err(), Some(RuleError::Occupied));
+        assert_eq!(pos.get_state(pt), PointState::Black);
+        for &pt in &[0, pos.xy_to_linear(10, 1), 10000] {
+            assert!(!pos.is_legal(Move::Linear(pt)));
+            assert_eq!(pos.play(Move::Linear(pt)).err(), Some(RuleError::OffBoard));
+        }
+        assert_eq!(pos.get_hash(), hash);
+        assert!(pos.is_legal(Move::Pass));
+        assert!(pos.is_legal(Move::Resign));
+    }
+
+    #[test]
+    fn test_is_legal() {
+        // is_legalはplayと同じ判定になる
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        for &rule_set in &[RuleSet::japanese(), RuleSet::chinese(), RuleSet::new_zealand()] {
+            let mut pos = Position9::new();
+            pos.set_rule_set(rule_set);
+            for _ in 0..200 {
+                for pt in pos.all_points() {
+                    let mov = Move::Linear(pt);
+                    let hash = pos.get_hash();
+                    let expected = pos.check_move(mov);
+                    assert_eq!(pos.get_hash(), hash);
+                    match pos.play(mov) {
+                        Ok(move_log) => {
+                            assert_eq!(expected, Ok(()));
+                            pos.undo_play(&move_log);
+                        },
+                        Err(e) => assert_eq!(expected, Err(e)),
+                    }
+                }
+                play_random_move(&mut pos, &mut rng);
+            }
+        }
+        let pos = ko_position(KoRule::Situational);
+        assert_eq!(pos.check_move(Move::Linear(pos.xy_to_linear(3, 3))), Err(RuleError::Ko));
+    }
+
+    #[test]
+    fn test_game_end() {
+        let mut pos = Position9::new();
+        pos.set_komi(0.5);
+        let e5 = Move::Linear(pos.xy_to_linear(5, 5));
+        pos.play(Move::Pass).unwrap();
+        pos.play(e5).unwrap();
+        assert_eq!(pos.get_consecutive_passes(), 0);
+        let move_log1 = pos.play(Move::Pass).unwrap();
+        assert!(!pos.is_game_over());
+        assert_eq!(pos.get_result(), None);
+        let move_log2 = pos.play(Move::Pass).unwrap();
+        assert!(pos.is_game_over());
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Color::White, WinReason::Score(81.5))));
+        pos.undo_play(&move_log2);
+        assert_eq!(pos.get_consecutive_passes(), 1);
+        pos.undo_play(&move_log1);
+        assert_eq!(pos.get_consecutive_passes(), 0);
+
+        let move_log = pos.play(Move::Resign).unwrap();
+        assert!(pos.is_game_over());
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Color::White, WinReason::Resign)));
+        assert_eq!(pos.play(Move::Pass).err(), Some(RuleError::GameOver));
+        assert!(!pos.is_legal(Move::Pass));
+        pos.undo_play(&move_log);
+        assert!(!pos.is_game_over());
+        assert_eq!(pos.get_turn(), Color::Black);
+    }
+
+    #[test]
+    fn test_game_result() {
+        for &(s, result) in &[
+            ("B+R", GameResult::Win(Color::Black, WinReason::Resign)),
+            ("W+3.5", GameResult::Win(Color::White, WinReason::Score(3.5))),
+            ("B+T", GameResult::Win(Color::Black, WinReason::Time)),
+            ("W+F", GameResult::Win(Color::White, WinReason::Forfeit)),
+            ("B+", GameResult::Win(Color::Black, WinReason::Unknown)),
+            ("0", GameResult::Jigo),
+            ("Void", GameResult::Void),
+            ("?", GameResult::Unknown),
+        ] {
+            assert_eq!(GameResult::parse(s), Some(result));
+            assert_eq!(result.to_string(), s);
+        }
+        assert_eq!(GameResult::parse("W+Resign"), Some(GameResult::Win(Color::White, WinReason::Resign)));
+        assert_eq!(GameResult::parse("Draw"), Some(GameResult::Jigo));
+        assert_eq!(GameResult::parse("B+x"), None);
+        assert_eq!(GameResult::parse("W+-1"), None);
+        assert_eq!(GameResult::parse(""), None);
+        assert_eq!(GameResult::from_score(-0.5).winner(), Some(Color::White));
+        assert_eq!(GameResult::from_score(0.0), GameResult::Jigo);
+    }
+
+    #[test]
+    fn test_tromp_taylor_score() {
+        let pos = Position5x7::from_string("\
+.X.O.
+XX.OO
+.....
+.....
+OOOOO
+.....
+.....").unwrap();
+        let score = pos.tromp_taylor_score();
+        assert_eq!(score, AreaScore {
+            black_stones: 3,
+            white_stones: 8,
+            black_territory: 1,
+            white_territory: 11,
+            dame: 12,
+            komi: 6.5,
+        });
+        assert_eq!(score.margin(), 4.0 - 19.0 - 6.5);
+        assert_eq!(score.result(), GameResult::Win(Color::White, WinReason::Score(21.5)));
+        // scoreは眼の点しか数えません。
+        assert_eq!(pos.score(), 4.0 - 9.0 - 6.5);
+
+        let score = Position9::new().tromp_taylor_score();
+        assert_eq!(score.dame, 81);
+        assert_eq!(score.margin(), -6.5);
+    }
+
+    #[test]
+    fn test_territory_score() {
+        let mut pos = Position5x7::from_string("\
+.XO..
+.XO..
+.XO..
+OXO..
+.XO.X
+.XO..
+.XO..").unwrap();
+        let dead = [pos.xy_to_linear(1, 4), pos.xy_to_linear(5, 5)];
+        let area = pos.area_score(&dead);
+        assert_eq!((area.black_area(), area.white_area(), area.dame), (14, 21, 0));
+        let territory = pos.territory_score(&dead, &[]);
+        assert_eq!(territory, TerritoryScore {
+            black_territory: 7,
+            white_territory: 14,
+            black_prisoners: 1,
+            white_prisoners: 1,
+            komi: 6.5,
+        });
+        assert_eq!(territory.margin(), area.margin());
+        assert_eq!(pos.tromp_taylor_score().dame, 19);
+
+        // セキの連に接する領域は地に数えません。
+        let territory = pos.territory_score(&dead, &[pos.xy_to_linear(2, 1)]);
+        assert_eq!((territory.black_territory, territory.white_territory), (0, 14));
+
+        // 対局中のアゲハマも数えます。
+        pos.set_prisoners(Color::White, 3);
+        assert_eq!(pos.territory_score(&dead, &[]).white_points(), 18);
+        assert_eq!(pos.area_score(&dead), area);
+    }
+
+    #[test]
+    fn test_rule_sets() {
+        assert_eq!(RuleSet::from_name("Japanese"), Some(RuleSet::japanese()));
+        assert_eq!(RuleSet::from_name("nz"), Some(RuleSet::new_zealand()));
+        assert_eq!(RuleSet::from_name("tromp-taylor"), Some(RuleSet::tromp_taylor()));
+        assert_eq!(RuleSet::from_name("korean"), None);
+        let mut pos = Position9::new();
+        assert_eq!(pos.get_rule_set(), RuleSet::default());
+        pos.set_rule_set(RuleSet::aga());
+        assert_eq!((pos.get_ko_rule(), pos.get_komi()), (KoRule::Situational, 6.5));
+
+        // 複数の石の自殺手
+        let diagram = "\
+O.X......
+XX.......
+.........
+.........
+.........
+.........
+.........
+.........
+.........";
+        let mut pos = Position9::from_string(diagram).unwrap();
+        pos.set_turn(Color::White);
+        let pt = pos.xy_to_linear(2, 1);
+        assert_eq!(pos.check_move(Move::Linear(pt)), Err(RuleError::Suicide));
+        pos.set_rule_set(RuleSet::new_zealand());
+        let hash = pos.get_hash();
+        assert_eq!(pos.check_move(Move::Linear(pt)), Ok(()));
+        let move_log = pos.play(Move::Linear(pt)).unwrap();
+        assert_eq!(pos.get_state(pt), PointState::Empty);
+        assert_eq!(pos.get_state(pos.xy_to_linear(1, 1)), PointState::Empty);
+        assert_eq!(pos.get_prisoners(Color::Black), 2);
+        assert_eq!(pos.get_turn(), Color::Black);
+        pos.undo_play(&move_log);
+        assert_eq!(pos.get_hash(), hash);
+        assert_eq!(pos.get_state(pos.xy_to_linear(1, 1)), PointState::White);
+        assert_eq!(pos.get_state(pt), PointState::Empty);
+        assert_eq!(pos.get_prisoners(Color::Black), 0);
+        // 1つの石の自殺手はどのルールでも禁止
+        pos.play(Move::Pass).unwrap();
+        pos.play(Move::Linear(pos.xy_to_linear(2, 1))).unwrap();
+        pos.set_turn(Color::White);
+        assert_eq!(pos.check_move(Move::Linear(pos.xy_to_linear(1, 1))), Err(RuleError::Suicide));
+        assert_eq!(pos.play(Move::Linear(pos.xy_to_linear(1, 1))).err(), Some(RuleError::Suicide));
+
+        // AGAルールではパスすると相手にアゲハマを1つ渡す
+        let mut pos = Position9::new();
+        pos.set_rule_set(RuleSet::aga());
+        let move_log = pos.play(Move::Pass).unwrap();
+        assert_eq!(pos.get_prisoners(Color::White), 1);
+        pos.undo_play(&move_log);
+        assert_eq!(pos.get_prisoners(Color::White), 0);
+        // 白が最後にパスするまで終局せず、地とアゲハマで数えても数え上げと同じ結果になる
+        for &(x, y) in &[(3, 3), (7, 7), (3, 7)] {
+            pos.play(Move::Linear(pos.xy_to_linear(x, y))).unwrap();
+        }
+        pos.play(Move::Pass).unwrap();
+        pos.play(Move::Pass).unwrap();
+        assert!(!pos.is_game_over());
+        assert_eq!(pos.get_result(), None);
+        pos.play(Move::Pass).unwrap();
+        assert!(pos.is_game_over());
+        assert_eq!(pos.get_prisoners(Color::Black), 2);
+        assert_eq!(pos.get_prisoners(Color::White), 1);
+        assert_eq!(pos.territory_score(&[], &[]).margin(), pos.area_score(&[]).margin());
+        assert_eq!(pos.get_result(), Some(GameResult::from_score(pos.area_score(&[]).margin())));
+
+        // 日本ルールは地とアゲハマで数える
+        let mut pos = Position9::from_string(diagram).unwrap();
+        pos.set_rule_set(RuleSet::japanese());
+        pos.set_komi(0.5);
+        pos.play(Move::Pass).unwrap();
+        pos.play(Move::Pass).unwrap();
+        assert_eq!(pos.rule_score(&[]), pos.territory_score(&[], &[]).margin());
+        assert_eq!(pos.get_result(), Some(GameResult::Win(Color::Black, WinReason::Score(75.5))));
+    }
+
+    #[test]
+    fn test_handicap() {
+        let to_string = |pos: &Position19, points: &[LinearCoord]| {
+            points.iter().map(|&pt| pos.str_coord(Move::Linear(pt))).collect::<Vec<_>>().join(" ")
+        };
+        let pos = Position19::new();
+        assert_eq!(to_string(&pos, &fixed_handicap_points(&pos, 9).unwrap()), "D4 Q16 D16 Q4 D10 Q10 K4 K16 K10");
+        assert_eq!(to_string(&pos, &fixed_handicap_points(&pos, 5).unwrap()), "D4 Q16 D16 Q4 K10");
+        assert_eq!(to_string(&pos, &fixed_handicap_points(&pos, 6).unwrap()), "D4 Q16 D16 Q4 D10 Q10");
+        assert_eq!(fixed_handicap_points(&pos, 1), None);
+        assert_eq!(fixed_handicap_points(&pos, 10), None);
+        let pos9 = Position9::new();
+        let points: Vec<_> = fixed_handicap_points(&pos9, 3).unwrap().iter().map(|&pt| pos9.str_coord(Move::Linear(pt))).collect();
+        assert_eq!(points, ["C3", "G7", "C7"]);
+        let pos13 = Position13::new();
+        assert_eq!(fixed_handicap_points(&pos13, 2).unwrap(), [pos13.xy_to_linear(4, 10), pos13.xy_to_linear(10, 4)]);
+        assert_eq!(fixed_handicap_points(&Position5x7::new(), 2), None);
+        assert_eq!(fixed_handicap_points(&Position21::new(), 2), None);
+
+        // 置き石の後は白番
+        let mut pos = Position19::new();
+        let points = fixed_handicap_points(&pos, 4).unwrap();
+        pos.set_handicap(&points).unwrap();
+        assert_eq!(pos.get_turn(), Color::White);
+        assert!(points.iter().all(|&pt| pos.get_state(pt) == PointState::Black));
+        assert_eq!(pos.get_hash(), pos.compute_hash());
+        let hash = pos.get_hash();
+        let pt = pos.xy_to_linear(10, 10);
+        assert_eq!(pos.set_handicap(&[pt, points[0]]), Err(RuleError::Occupied));
+        assert_eq!(pos.set_handicap(&[pt, pt]), Err(RuleError::Occupied));
+        assert_eq!(pos.set_handicap(&[pt, 0]), Err(RuleError::OffBoard));
+        assert_eq!(pos.get_hash(), hash);
+
+        // 自由置き石
+        let pos = Position19::new();
+        assert_eq!(free_handicap_points(&pos, 4), fixed_handicap_points(&pos, 4).unwrap());
+        let points = free_handicap_points(&pos, 13);
+        assert_eq!(points.len(), 13);
+        assert!(points.iter().enumerate().all(|(i, pt)| !points[..i].contains(pt)));
+        let pos = Position5x7::new();
+        assert_eq!(free_handicap_points(&pos, 2), [pos.xy_to_linear(1, 1), pos.xy_to_linear(5, 7)]);
+        assert_eq!(free_handicap_points(&pos, 100).len(), 34);
+    }
+
+    #[test]
+    fn test_unconditional_life() {
+        // 眼が3つある黒の連
+        let pos = Position5x7::from_string("\
+.X.X.
+XXXXX
+..O..
+.....
+.....
+.....
+.....").unwrap();
+        let life = unconditional_life(&pos, Color::Black);
+        assert_eq!(life.strings.len(), 1);
+        assert_eq!(life.strings[0].len(), 7);
+        assert_eq!(life.regions, [vec![pos.xy_to_linear(1, 1)], vec![pos.xy_to_linear(3, 1)], vec![pos.xy_to_linear(5, 1)]]);
+        let marks = life.marks(&pos);
+        assert_eq!(pos.all_points().filter(|&pt| marks[pt as usize]).count(), 10);
+        assert_eq!(unconditional_life(&pos, Color::White), UnconditionalLife::default());
+
+        // 眼が1つでは活きていません。
+        let pos = Position5x7::from_string("\
+.X...
+XX...
+.....
+.....
+.....
+.....
+.....").unwrap();
+        assert_eq!(unconditional_life(&pos, Color::Black), UnconditionalLife::default());
+
+        // 中に白石がある領域も黒の生きた領域です。中の白石は死に石です。
+        let pos = Position5x7::from_string("\
+.XOX.
+XX.XX
+XXXXX
+.....
+OOOOO
+O.O.O
+OOOOO").unwrap();
+        let life = unconditional_life(&pos, Color::Black);
+        assert_eq!(life.strings.len(), 1);
+        assert!(life.regions.contains(&vec![pos.xy_to_linear(3, 1), pos.xy_to_linear(3, 2)]));
+        assert!(!life.regions.iter().any(|region| region.contains(&pos.xy_to_linear(1, 4))));
+        // 白も眼が2つあり活きています。(3, 1)の白石は別の連で、活きていません。
+        let life = unconditional_life(&pos, Color::White);
+        assert_eq!(life.strings.len(), 1);
+        assert_eq!(life.strings[0].len(), 13);
+        assert_eq!(life.regions, [vec![pos.xy_to_linear(2, 6)], vec![pos.xy_to_linear(4, 6)]]);
+    }
+
+    #[test]
+    fn test_eye_shapes() {
+        let pos = Position9::new();
+        let classify = |xys: &[(u8, u8)]| {
+            let points: Vec<_> = xys.iter().map(|&(x, y)| pos.xy_to_linear(x, y)).collect();
+            let eye_space = classify_eye_space(&pos, &points);
+            (eye_space.shape, eye_space.vital.map(|pt| pos.linear_to_xy(pt)), eye_space.eyes())
+        };
+        assert_eq!(classify(&[(1, 1)]), (EyeShape::One, None, Eyes::One));
+        assert_eq!(classify(&[(1, 1), (2, 1)]), (EyeShape::Two, None, Eyes::One));
+        assert_eq!(classify(&[(3, 3), (4, 3), (5, 3)]), (EyeShape::StraightThree, Some((4, 3)), Eyes::Unsettled));
+        assert_eq!(classify(&[(3, 3), (4, 3), (4, 4)]), (EyeShape::BentThree, Some((4, 3)), Eyes::Unsettled));
+        assert_eq!(classify(&[(3, 3), (4, 3), (3, 4), (4, 4)]), (EyeShape::SquareFour, None, Eyes::One));
+        assert_eq!(classify(&[(3, 3), (4, 3), (5, 3), (4, 4)]), (EyeShape::PyramidFour, Some((4, 3)), Eyes::Unsettled));
+        assert_eq!(classify(&[(3, 3), (3, 4), (3, 5), (3, 6)]), (EyeShape::StraightFour, None, Eyes::Two));
+        assert_eq!(classify(&[(3, 3), (4, 3), (5, 3), (5, 4)]), (EyeShape::BentFour, None, Eyes::Two));
+        assert_eq!(classify(&[(3, 3), (4, 3), (3, 4), (4, 4), (5, 4)]), (EyeShape::BulkyFive, Some((4, 4)), Eyes::Unsettled));
+        assert_eq!(classify(&[(4, 3), (3, 4), (4, 4), (5, 4), (4, 5)]), (EyeShape::CrossedFive, Some((4, 4)), Eyes::Unsettled));
+        assert_eq!(classify(&[(4, 3), (3, 4), (4, 4), (5, 4), (4, 5), (5, 5)]), (EyeShape::RabbitySix, Some((4, 4)), Eyes::Unsettled));
+        assert_eq!(classify(&[(1, 1), (2, 1), (3, 1), (4, 1), (5, 1)]), (EyeShape::Other, None, Eyes::Two));
+        assert_eq!(classify(&[(1, 1), (3, 1)]), (EyeShape::Other, None, Eyes::Two));
+
+        let pos = Position9::from_string("\
+.O.OOOO..
+XOOXXXX..
+XXXX.....
+.........
+.........
+.........
+.........
+.........
+.........").unwrap();
+        let mut string = GoString::new();
+        pos.string_at(pos.xy_to_linear(2, 1), &mut string);
+        assert!(string.is_nakade_shape(&pos));
+        let mut string = GoString::new();
+        pos.string_at(pos.xy_to_linear(4, 1), &mut string);
+        assert!(!string.is_nakade_shape(&pos));
+    }
+
+    #[test]
+    fn test_vital_point() {
+        let mut pos = Position9::from_string("\
+...X.....
+XXXX.....
+.........
+.........
+.........
+.........
+.........
+.........
+.........").unwrap();
+        let spaces = eye_spaces(&pos, 6);
+        assert_eq!(spaces.len(), 1);
+        assert_eq!(spaces[0].0, Color::Black);
+        assert_eq!(spaces[0].2.shape, EyeShape::StraightThree);
+        pos.set_turn(Color::White);
+        assert_eq!(play_vital_point(&mut pos), Some(Move::Linear(pos.xy_to_linear(2, 1))));
+        assert_eq!(play_vital_point(&mut pos), None);
+        // 急所に置かれた石は死に石として眼形に含まれます。
+        let spaces = eye_spaces(&pos, 6);
+        assert_eq!(spaces.len(), 1);
+        assert_eq!(spaces[0].2.vital, Some(pos.xy_to_linear(2, 1)));
+
+        // 死に石を含む丁四の急所に打ちます。
+        let mut pos = Position9::from_string("\
+O..X.....
+X.X......
+.X.......
+.........
+.........
+.........
+.........
+.........
+.........").unwrap();
+        let spaces = eye_spaces(&pos, 6);
+        assert_eq!(spaces.len(), 1);
+        assert_eq!((spaces[0].0, spaces[0].1.len()), (Color::Black, 4));
+        assert_eq!(spaces[0].2.shape, EyeShape::PyramidFour);
+        assert_eq!(play_vital_point(&mut pos), Some(Move::Linear(pos.xy_to_linear(2, 1))));
+
+        let config = PlayoutConfig { vital_points: true, record_moves: true, ..PlayoutConfig::default() };
+        let result1 = playout(Position9::new(), &config, &mut XorShiftRng::from_seed([1, 2, 3, 4]));
+        let result2 = playout(Position9::new(), &config, &mut XorShiftRng::from_seed([1, 2, 3, 4]));
+        assert!(result1.finished);
+        assert_eq!(result1.moves, result2.moves);
+    }
+
+    #[test]
+    fn test_seki() {
+        let diagram = "\
+.XXXX
+XXXXX
+XXXXX
+XX.OO
+OOOOO
+OOOOO
+OOOO.";
+        let mut pos = Position5x7::from_string(diagram).unwrap();
+        let shared = pos.xy_to_linear(3, 4);
+        assert!(is_seki_liberty(&pos, shared));
+        assert!(!is_seki_liberty(&pos, pos.xy_to_linear(1, 1)));
+        let seki = pos.seki();
+        assert_eq!(seki.strings.len(), 2);
+        assert_eq!(seki.stones().len(), 32);
+        assert_eq!(seki.liberties, [shared]);
+
+        // セキの眼は日本ルールでは地に数えません。共有の呼吸点はどちらにも数えません。
+        assert_eq!(pos.score(), -6.5);
+        assert_eq!(pos.tromp_taylor_score().dame, 1);
+        assert_eq!(pos.territory_score(&[], &[]).black_territory, 1);
+        pos.set_rule_set(RuleSet::japanese());
+        assert_eq!(pos.rule_score(&[]), pos.territory_score(&[], &seki.stones()).margin());
+        assert_eq!(pos.territory_score(&[], &seki.stones()).black_territory, 0);
+
+        // keep_sekiを指定したプレイアウトはセキを壊しません。
+        let config = PlayoutConfig { keep_seki: true, ..PlayoutConfig::default() };
+        let result = playout(pos.clone(), &config, &mut XorShiftRng::from_seed([1, 2, 3, 4]));
+        assert_eq!(result.num_moves, 2);
+        assert_eq!(result.score, -6.5);
+
+        // 白の呼吸点が多ければ黒を取れるのでセキではありません。
+        let mut pos = Position5x7::from_string(diagram).unwrap();
+        pos.set_state(pos.xy_to_linear(5, 6), PointState::Empty);
+        assert!(!is_seki_liberty(&pos, shared));
+        assert!(pos.seki().is_empty());
+    }
+
+    #[test]
+    fn test_ladder() {
+        let mut pos = Position19::new();
+        let white = pos.xy_to_linear(10, 10);
+        pos.set_state(white, PointState::White);
+        for &(x, y) in &[(10, 9), (9, 10), (11, 11)] {
+            pos.set_state(pos.xy_to_linear(x, y), PointState::Black);
+        }
+        let to_strings = |pos: &Position19, moves: &[Move]| moves.iter().map(|&m| pos.str_coord(m)).collect::<Vec<_>>();
+        let hash = pos.get_hash();
+        let ladder = read_ladder(&mut pos, white).unwrap();
+        assert!(ladder.captured);
+        assert_eq!(ladder.moves.len(), 35);
+        assert_eq!(to_strings(&pos, &ladder.moves[..4]), ["L10", "K9", "K8", "J9"]);
+        // 盤端まで追って取ります。
+        assert_eq!(to_strings(&pos, &ladder.moves[31..]), ["B2", "B1", "A2", "A3"]);
+        assert_eq!(pos.get_hash(), hash);
+        assert_eq!(pos.get_turn(), Color::Black);
+        assert!(ladder_captures(&mut pos).contains(&pos.xy_to_linear(11, 10)));
+        assert_eq!(pos.get_hash(), hash);
+        let empty = pos.xy_to_linear(1, 1);
+        assert_eq!(read_ladder(&mut pos, empty), None);
+
+        // アタリの連は逃げる側の手番で読みます。
+        let mut atari = pos.clone();
+        atari.play(Move::Linear(atari.xy_to_linear(11, 10))).unwrap();
+        let ladder = read_ladder(&mut atari, white).unwrap();
+        assert!(ladder.captured);
+        assert_eq!(ladder.moves.len(), 34);
+        assert!(ladder_escapes(&mut atari).is_empty());
+
+        // シチョウアタリがあれば逃げられます。
+        let mut breaker = pos.clone();
+        for &(x, y) in &[(3, 17), (17, 3)] {
+            breaker.set_state(breaker.xy_to_linear(x, y), PointState::White);
+        }
+        assert!(!read_ladder(&mut breaker, white).unwrap().captured);
+        assert!(ladder_captures(&mut breaker).is_empty());
+
+        // 追う側の石を取って逃げます。
+        let mut capture = pos.clone();
+        for &(x, y) in &[(10, 8), (9, 9)] {
+            capture.set_state(capture.xy_to_linear(x, y), PointState::White);
+        }
+        let ladder = read_ladder(&mut capture, white).unwrap();
+        assert!(!ladder.captured);
+        assert!(ladder.moves.contains(&Move::Linear(capture.xy_to_linear(11, 9))));
+        capture.play(Move::Linear(capture.xy_to_linear(10, 11))).unwrap();
+        let escape = capture.xy_to_linear(11, 9);
+        assert!(ladder_escapes(&mut capture).contains(&escape));
+    }
+
+    #[test]
+    fn test_tactics() {
+        let mut pos = Position19::new();
+        let white = pos.xy_to_linear(10, 10);
+        pos.set_state(white, PointState::White);
+        for &(x, y) in &[(10, 9), (9, 10), (11, 11)] {
+            pos.set_state(pos.xy_to_linear(x, y), PointState::Black);
+        }
+        let hash = pos.get_hash();
+        let board = Position19::new();
+        let key_move = |tactic: Tactic| tactic.key_move.map(|m| board.str_coord(m));
+
+        // シチョウで取れます。白が先なら伸びて呼吸点が3つになります。
+        let tactic = can_capture(&mut pos, white, 10000).unwrap();
+        assert_eq!((tactic.result, key_move(tactic)), (TacticalResult::LikelySuccess, Some("L10".to_string())));
+        let tactic = can_escape(&mut pos, white, 10000).unwrap();
+        assert_eq!((tactic.result, key_move(tactic)), (TacticalResult::LikelySuccess, Some("L10".to_string())));
+        assert_eq!((pos.get_hash(), pos.get_turn()), (hash, Color::Black));
+        let mut atari = pos.clone();
+        atari.play(Move::Linear(atari.xy_to_linear(11, 10))).unwrap();
+        assert_eq!(can_escape(&mut atari, white, 10000).unwrap().result, TacticalResult::LikelyFailure);
+
+        // ノード数が足りなければ結果不明です。
+        let tactic = can_capture(&mut pos, white, 10).unwrap();
+        assert_eq!((tactic.result, tactic.nodes), (TacticalResult::Unknown, 10));
+        let empty = pos.xy_to_linear(1, 1);
+        assert_eq!(can_capture(&mut pos, empty, 10000), None);
+        let mut free = Position19::new();
+        free.set_state(white, PointState::White);
+        assert_eq!(can_capture(&mut free, white, 10000), None);
+
+        // シチョウアタリがあれば取れません。
+        let mut breaker = pos.clone();
+        for &(x, y) in &[(3, 17), (17, 3)] {
+            breaker.set_state(breaker.xy_to_linear(x, y), PointState::White);
+        }
+        assert_eq!(can_capture(&mut breaker, white, 10000).unwrap().result, TacticalResult::LikelyFailure);
+
+        // シチョウでは取れなくてもゲタで取れます。
+        let mut geta = Position19::new();
+        geta.set_state(white, PointState::White);
+        for &(x, y) in &[(10, 9), (9, 10), (9, 11), (11, 9)] {
+            geta.set_state(geta.xy_to_linear(x, y), PointState::Black);
+        }
+        for &(x, y) in &[(13, 13), (7, 7), (13, 7), (7, 13)] {
+            geta.set_state(geta.xy_to_linear(x, y), PointState::White);
+        }
+        assert!(!read_ladder(&mut geta, white).unwrap().captured);
+        let tactic = can_capture(&mut geta, white, 10000).unwrap();
+        assert_eq!((tactic.result, key_move(tactic)), (TacticalResult::LikelySuccess, Some("L9".to_string())));
+
+        // 呼吸点が3つの石もゲタで取れます。逃げる側は途中で呼吸点が3つに戻ります。
+        let mut net = Position19::new();
+        net.set_state(white, PointState::White);
+        for &(x, y) in &[(10, 9), (9, 8), (9, 11), (11, 11)] {
+            net.set_state(net.xy_to_linear(x, y), PointState::Black);
+        }
+        assert_eq!(net.num_liberties_at(white), 3);
+        let tactic = can_capture(&mut net, white, 10000).unwrap();
+        assert_eq!((tactic.result, key_move(tactic)), (TacticalResult::LikelySuccess, Some("L10".to_string())));
+    }
+
+    #[test]
+    fn test_semeai() {
+        let diagram = "\
+XOX......
+XOX......
+.........
+.........
+.........
+.........
+.........
+.........
+.........";
+        // 外ダメが1つずつなら先に打った側が勝ちます。
+        let pos = Position9::from_string(diagram).unwrap();
+        let (black, white) = (pos.xy_to_linear(1, 1), pos.xy_to_linear(2, 1));
+        let semeai = evaluate_semeai(&pos, white, black).unwrap();
+        assert_eq!((semeai.black.outside.len(), semeai.white.outside.len(), semeai.shared.len()), (1, 1, 0));
+        assert_eq!(semeai.black_first, SemeaiVerdict {
+            outcome: SemeaiOutcome::Win(Color::Black),
+            key_move: Move::Linear(pos.xy_to_linear(2, 3)),
+        });
+        assert_eq!(semeai.verdict(Color::White), SemeaiVerdict {
+            outcome: SemeaiOutcome::Win(Color::White),
+            key_move: Move::Linear(pos.xy_to_linear(1, 3)),
+        });
+        assert_eq!(urgent_semeai_move(&pos), Some(pos.xy_to_linear(2, 3)));
+        assert_eq!(evaluate_semeai(&pos, black, pos.xy_to_linear(3, 1)), None);
+        assert_eq!(evaluate_semeai(&pos, black, pos.xy_to_linear(5, 5)), None);
+        let mut pos = pos.clone();
+        assert_eq!(play_semeai_move(&mut pos), Some(Move::Linear(pos.xy_to_linear(2, 3))));
+        // 勝ちが決まった攻め合いには打ちません。
+        pos.play(Move::Pass).unwrap();
+        assert_eq!(urgent_semeai_move(&pos), None);
+
+        // 眼がある方が勝ちます。
+        let pos = Position5x7::from_string("\
+.XXXX
+XXXXX
+XXXXX
+XX.OO
+OOOOO
+OOOOO
+OOO.X").unwrap();
+        let semeai = evaluate_semeai(&pos, pos.xy_to_linear(1, 2), pos.xy_to_linear(1, 5)).unwrap();
+        assert_eq!((semeai.black.eyes, semeai.black.eye_liberties, semeai.white.eyes), (1, 1, 0));
+        assert_eq!(semeai.black_first.outcome, SemeaiOutcome::Win(Color::Black));
+        assert_eq!(semeai.black_first.key_move, Move::Linear(pos.xy_to_linear(4, 7)));
+        assert_eq!(semeai.white_first.outcome, SemeaiOutcome::Win(Color::Black));
+
+        // 眼が1つずつで内ダメが1つならセキです。
+        let pos = Position5x7::from_string("\
+.XXXX
+XXXXX
+XXXXX
+XX.OO
+OOOOO
+OOOOO
+OOOO.").unwrap();
+        let semeai = evaluate_semeai(&pos, pos.xy_to_linear(1, 2), pos.xy_to_linear(1, 5)).unwrap();
+        assert_eq!(semeai.black_first, SemeaiVerdict { outcome: SemeaiOutcome::Seki, key_move: Move::Pass });
+        assert_eq!(semeai.white_first.outcome, SemeaiOutcome::Seki);
+
+        let config = PlayoutConfig { semeai_moves: true, ..PlayoutConfig::default() };
+        assert!(playout(Position9::new(), &config, &mut thread_rng()).finished);
+    }
+
+    #[test]
+    fn test_incremental_strings() {
+        // 差分更新した連の石と呼吸点の数は、string_atで求めた連と一致します。
+        fn check<T: Rule>(pos: &T) {
+            for pt in pos.all_points().filter(|&pt| pos.get_state(pt).is_stone()) {
+                let mut string = GoString::new();
+                pos.string_at(pt, &mut string);
+                assert_eq!(pos.num_liberties_at(pt), string.num_liberties());
+                assert_eq!(pos.string_size_at(pt), string.points.len());
+            }
+        }
+
+        let mut rng = XorShiftRng::from_seed([5, 6, 7, 8]);
+        let mut pos = Position9::new();
+        pos.set_rule_set(RuleSet::new_zealand());
+        for _ in 0..300 {
+            for pt in pos.empties() {
+                if let Ok(move_log) = pos.play(Move::Linear(pt)) {
+                    check(&pos);
+                    pos.undo_play(&move_log);
+                }
+            }
+            check(&pos);
+            play_random_move(&mut pos, &mut rng);
+            check(&pos);
+        }
+
+        // 連の途中の石を取り除くと、残りの石は別々の連になります。
+        let mut pos = Position9::from_string("\
+.........
+.XXX.....
+.........
+.........
+.........
+.........
+.........
+.........
+.........").unwrap();
+        assert_eq!(pos.num_liberties_at(pos.xy_to_linear(2, 2)), 8);
+        pos.set_state(pos.xy_to_linear(3, 2), PointState::Empty);
+        assert_eq!(pos.string_size_at(pos.xy_to_linear(2, 2)), 1);
+        assert_eq!(pos.num_liberties_at(pos.xy_to_linear(4, 2)), 4);
+        pos.set_state(pos.xy_to_linear(3, 2), PointState::White);
+        check(&pos);
+        assert_eq!(pos.num_liberties_at(pos.xy_to_linear(3, 2)), 2);
+        assert_eq!(pos.get_hash(), pos.compute_hash());
+    }
+
+    #[test]
+    fn test_undo_play() {
+        let mut pos = Position19::new();
+        let white = pos.xy_to_linear(1, 1);
+        pos.set_state(white, PointState::White);
+        pos.set_state(pos.xy_to_linear(1, 2), PointState::Black);
+        let move_log = pos.play(Move::Linear(pos.xy_to_linear(2, 1))).unwrap();
+        assert_eq!(pos.get_state(white), PointState::Empty);
+        assert_eq!(pos.get_turn(), Color::White);
+        assert_eq!(pos.get_prisoners(Color::Black), 1);
+        pos.undo_play(&move_log);
+        assert_eq!(pos.get_prisoners(Color::Black), 0);
+        assert_eq!(pos.get_state(white), PointState::White);
+        assert_eq!(pos.get_state(pos.xy_to_linear(2, 1)), PointState::Empty);
+        assert_eq!(pos.get_turn(), Color::Black);
+
+        // play_asは手番ではない色の着手をWrongTurnで拒否します。
+        let hash = pos.get_hash();
+        assert_eq!(pos.play_as(Color::White, Move::Pass).err(), Some(RuleError::WrongTurn));
+        assert_eq!(pos.get_hash(), hash);
+        assert!(pos.play_as(Color::Black, Move::Linear(pos.xy_to_linear(2, 1))).is_ok());
+        assert_eq!(pos.get_state(white), PointState::Empty);
+    }
+
+    /// 白がコウを取った直後の局面を返します。(3, 3)が黒のコウの取り返しの点です。
+    fn ko_position(ko_rule: KoRule) -> Position19 {
+        let mut pos = Position19::new();
+        for &(x, y) in &[(2, 2), (1, 3), (3, 3), (2, 4)] {
+            pos.set_state(pos.xy_to_linear(x, y), PointState::Black);
+        }
+        for &(x, y) in &[(3, 2), (4, 3), (3, 4)] {
+            pos.set_state(pos.xy_to_linear(x, y), PointState::White);
+        }
+        pos.set_ko_rule(ko_rule);
+        pos.set_turn(Color::White);
+        let pt = pos.xy_to_linear(2, 3);
+        assert!(pos.play(Move::Linear(pt)).is_ok());
+        pos
+    }
+
+    #[test]
+    fn test_superko() {
+        for &ko_rule in &[KoRule::Simple, KoRule::Positional, KoRule::Situational] {
+            let mut pos = ko_position(ko_rule);
+            let recapture = Move::Linear(pos.xy_to_linear(3, 3));
+            assert_eq!(pos.play(recapture).err(), Some(RuleError::Ko));
+            assert!(pos.play(Move::Pass).is_ok());
+            let move_log = pos.play(Move::Pass).unwrap();
+            let expected = pos.check_move(recapture);
+            let result = pos.play(recapture);
+            assert_eq!(result.as_ref().err(), expected.err().as_ref());
+            if ko_rule == KoRule::Simple {
+                assert!(result.is_ok());
+                assert_eq!(pos.get_history().len(), 4);
+            } else {
+                assert_eq!(result.err(), Some(RuleError::Superko));
+                assert_eq!(pos.get_state(pos.xy_to_linear(3, 3)), PointState::Empty);
+                assert_eq!(pos.get_turn(), Color::Black);
+                // undoすると履歴からも取り除かれます。
+                assert_eq!(pos.get_history().len(), 3);
+                pos.undo_play(&move_log);
+                assert_eq!(pos.get_history().len(), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_superko_after_rule_change() {
+        // 単純なコウのルールで打った局面も履歴に残り、途中でスーパーコウに変えると使われます。
+        let mut pos = ko_position(KoRule::Simple);
+        let recapture = Move::Linear(pos.xy_to_linear(3, 3));
+        assert!(pos.play(Move::Pass).is_ok());
+        assert!(pos.play(Move::Pass).is_ok());
+        pos.set_ko_rule(KoRule::Positional);
+        assert_eq!(pos.check_move(recapture), Err(RuleError::Superko));
+        assert_eq!(pos.play(recapture).err(), Some(RuleError::Superko));
+        pos.set_ko_rule(KoRule::Simple);
+        assert!(pos.play(recapture).is_ok());
+    }
+
+    #[test]
+    fn test_history_repetition() {
+        let mut history = History::new();
+        history.push(1, Color::Black);
+        assert!(!history.is_repetition(KoRule::Simple, 1, Color::Black));
+        assert!(history.is_repetition(KoRule::Positional, 1, Color::White));
+        assert!(!history.is_repetition(KoRule::Situational, 1, Color::White));
+        assert!(history.is_repetition(KoRule::Situational, 1, Color::Black));
+        assert!(!history.is_repetition(KoRule::Positional, 2, Color::Black));
+    }
+
+    #[test]
+    fn test_zobrist_hash() {
+        let mut rng = thread_rng();
+        let mut pos = Position19::new();
+        assert_eq!(pos.get_hash(), 0);
+        let mut logs = Vec::new();
+        let mut hashes = Vec::new();
+        for _ in 0..300 {
+            let candidates = pos.empties();
+            let pt = candidates[rng.gen_range(0, candidates.len())];
+            let hash = pos.get_hash();
+            if let Ok(move_log) = pos.play(Move::Linear(pt)) {
+                logs.push(move_log);
+                hashes.push(hash);
+            }
+            assert_eq!(pos.get_hash(), pos.compute_hash());
+        }
+        while let Some(move_log) = logs.pop() {
+            pos.undo_play(&move_log);
+            assert_eq!(pos.get_hash(), hashes.pop().unwrap());
+        }
+        assert_eq!(pos.get_hash(), 0);
+    }
+
+    #[test]
+    fn test_zobrist_transposition() {
+        let mut pos1 = Position19::new();
+        let mut pos2 = Position19::new();
+        for s in &["D4", "Q16", "Q4"] {
+            pos1.play(pos1.algebraic_to_move(s).unwrap()).unwrap();
+        }
+        for s in &["Q4", "Q16", "D4"] {
+            pos2.play(pos2.algebraic_to_move(s).unwrap()).unwrap();
+        }
+        assert_eq!(pos1.get_hash(), pos2.get_hash());
+        pos2.play(Move::Pass).unwrap();
+        assert!(pos1.get_hash() != pos2.get_hash());
+        assert_eq!(pos1.get_board_hash(), pos2.get_board_hash());
+    }
+
+    #[test]
+    fn test_game_navigation() {
+        let mut game = Game::new(Position9::new());
+        let moves: Vec<Move> = ["E5", "C3", "G7"].iter().map(|s| game.position().algebraic_to_move(s).unwrap()).collect();
+        for &mov in &moves {
+            game.play(mov).unwrap();
+        }
+        assert_eq!(game.move_number(), 3);
+        assert_eq!(game.current_move(), Some((Color::Black, moves[2])));
+        assert_eq!(game.position().get_turn(), Color::White);
+
+        assert_eq!(game.go_to_move(1), 1);
+        assert_eq!(game.position().get_state(pos_of(&game, "C3")), PointState::Empty);
+        assert_eq!(game.position().get_turn(), Color::White);
+        assert!(game.forward());
+        assert_eq!(game.position().get_state(pos_of(&game, "C3")), PointState::White);
+        assert_eq!(game.go_to_move(10), 3);
+        assert_eq!(game.go_to_move(0), 0);
+        assert!(!game.back());
+        assert_eq!(game.position().empties().len(), 81);
+    }
+
+    fn pos_of<T: Rule>(game: &Game<T>, s: &str) -> LinearCoord {
+        match game.position().algebraic_to_move(s).unwrap() {
+            Move::Linear(pt) => pt,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_game_variations() {
+        let mut game = Game::new(Position9::new());
+        let e5 = game.position().algebraic_to_move("E5").unwrap();
+        let c3 = game.position().algebraic_to_move("C3").unwrap();
+        let g7 = game.position().algebraic_to_move("G7").unwrap();
+        game.play(e5).unwrap();
+        game.play(c3).unwrap();
+        game.set_comment("main");
+        game.back();
+        game.play_as(Color::Black, g7).unwrap();
+        game.set_property("MN", "2");
+        assert_eq!(game.num_variations(), 2);
+        assert_eq!(game.variation_index(), 1);
+        assert_eq!(game.comment(), "");
+        assert_eq!(game.get_property("MN"), Some("2"));
+        assert!(game.switch_variation(0));
+        assert_eq!(game.current_move(), Some((Color::White, c3)));
+        assert_eq!(game.comment(), "main");
+        assert_eq!(game.position().get_state(pos_of(&game, "G7")), PointState::Empty);
+        assert!(!game.switch_variation(2));
+        // 同じ着手は既存のノードに移動します。
+        game.back();
+        game.play(c3).unwrap();
+        assert_eq!(game.variation_index(), 0);
+        assert_eq!(game.num_variations(), 2);
+        game.go_to_move(0);
+        assert_eq!(game.num_children(), 1);
+    }
+
     #[test]
     fn test_rollout() {
-        assert!(rollout().0 < 1000);
+        assert!(rollout::<Position9>().0 < 1000);
+        assert!(rollout::<Position13>().0 < 1000);
+        assert!(rollout::<Position19>().0 < 1000);
+    }
+
+    #[test]
+    fn test_parallel_rollout() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Position19>();
+
+        fn playouts(seed: u32) -> Vec<(u64, f32)> {
+            let mut rng = XorShiftRng::from_seed([seed, 2, 3, 4]);
+            (0..5).map(|_| {
+                let result = playout(Position19::new(), &PlayoutConfig::default(), &mut rng);
+                assert!(result.position.check_legal());
+                (result.position.get_hash(), result.score)
+            }).collect()
+        }
+
+        // 同時に実行しても、1スレッドで順に実行したときと同じ結果になる
+        let handles: Vec<_> = (1..5).map(|seed| ::std::thread::spawn(move || playouts(seed))).collect();
+        for (seed, handle) in (1..5).zip(handles) {
+            assert_eq!(handle.join().unwrap(), playouts(seed));
+        }
+    }
+
+    #[test]
+    fn test_playout() {
+        let config = PlayoutConfig { record_moves: true, ..PlayoutConfig::default() };
+        let result1 = playout(Position9::new(), &config, &mut XorShiftRng::from_seed([1, 2, 3, 4]));
+        let result2 = playout(Position9::new(), &config, &mut XorShiftRng::from_seed([1, 2, 3, 4]));
+        assert!(result1.finished);
+        assert_eq!(result1.score, result2.score);
+        assert_eq!(result1.moves, result2.moves);
+        assert_eq!(result1.position.get_hash(), result2.position.get_hash());
+
+        // 記録した着手を再生すると同じ最終局面になる
+        let moves = result1.moves.unwrap();
+        assert_eq!(moves.len(), result1.num_moves);
+        let mut pos = Position9::new();
+        for &mov in &moves {
+            pos.play(mov).unwrap();
+        }
+        assert_eq!(pos.get_hash(), result1.position.get_hash());
+        assert_eq!(pos.score(), result1.score);
+
+        let config = PlayoutConfig { max_moves: 10, ..PlayoutConfig::default() };
+        let result = playout(Position9::new(), &config, &mut thread_rng());
+        assert_eq!(result.num_moves, 10);
+        assert!(!result.finished);
+        assert!(result.moves.is_none());
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(Position9::new().empties().len(), 81);
+        assert_eq!(Position13::new().empties().len(), 169);
+        assert_eq!(Position19::new().empties().len(), 361);
+        let pos = Position13::new();
+        assert_eq!(pos.algebraic_to_move("N13").map(|m| pos.str_coord(m)).ok(), Some("N13".to_string()));
+        assert!(pos.algebraic_to_move("O1").is_err());
+    }
+
+    /// 全ての交点で座標の変換が往復できるか調べます。
+    fn check_coordinates<T: Rule + Default>() {
+        let pos = T::default();
+        let empties = pos.empties();
+        assert_eq!(empties.len(), (pos.get_width() * pos.get_height()) as usize);
+        for pt in empties {
+            let (x, y) = pos.linear_to_xy(pt);
+            assert_eq!(pos.xy_to_linear(x, y), pt);
+            let s = pos.str_coord(Move::Linear(pt));
+            assert!(!s.starts_with('I'));
+            assert_eq!(pos.algebraic_to_move(&s).ok(), Some(Move::Linear(pt)));
+        }
+    }
+
+    #[test]
+    fn test_large_sizes() {
+        check_coordinates::<Position9>();
+        check_coordinates::<Position19>();
+        check_coordinates::<Position21>();
+        check_coordinates::<Position23>();
+        check_coordinates::<Position25>();
+        let pos = Position25::new();
+        assert_eq!(pos.str_coord(Move::Linear(pos.xy_to_linear(25, 1))), "Z25");
+        assert_eq!(pos.str_coord(Move::Linear(pos.xy_to_linear(20, 25))), "U1");
+        assert!(pos.algebraic_to_move("A26").is_err());
+        assert!(format!("{}", pos).ends_with("T U V W X Y Z \n\n"));
+        assert!(rollout::<Position21>().0 < 1000);
+    }
+
+    #[test]
+    fn test_rectangular() {
+        let pos = Position5x7::new();
+        assert_eq!((pos.get_width(), pos.get_height()), (5, 7));
+        assert_eq!(pos.empties().len(), 35);
+        for pt in pos.empties() {
+            let (x, y) = pos.linear_to_xy(pt);
+            assert_eq!(pos.xy_to_linear(x, y), pt);
+            let s = pos.str_coord(Move::Linear(pt));
+            assert_eq!(pos.algebraic_to_move(&s).ok(), Some(Move::Linear(pt)));
+            let n_out = pos.adjacencies_at(pt).iter().filter(|&&a| !pos.is_on_board(a)).count();
+            let n_edge = [x == 1, x == 5, y == 1, y == 7].iter().filter(|&&b| b).count();
+            assert_eq!(n_out, n_edge);
+        }
+        assert_eq!(pos.str_coord(Move::Linear(pos.xy_to_linear(5, 1))), "E7");
+        assert!(pos.algebraic_to_move("F1").is_err());
+        assert!(pos.algebraic_to_move("A8").is_err());
+        assert_eq!(pos.diagonal_neighbors(pos.xy_to_linear(2, 2))[0], pos.xy_to_linear(3, 1));
+        assert!(format!("{}", pos).starts_with(" 7  . . . . . \n"));
+        assert!(format!("{}", pos).ends_with("    A B C D E \n\n"));
+    }
+
+    #[test]
+    fn test_rectangular_from_string() {
+        let mut pos = Position9x13::from_string("\
+.........
+.........
+.........
+.........
+.........
+.........
+.........
+.........
+.........
+.........
+.........
+X........
+O........").unwrap();
+        let white = pos.xy_to_linear(1, 13);
+        assert_eq!(pos.get_state(white), PointState::White);
+        let mut string = GoString::new();
+        pos.string_at(white, &mut string);
+        assert_eq!(string.liberties.as_slice(), &[pos.xy_to_linear(2, 13)]);
+        pos.play(pos.algebraic_to_move("B1").unwrap()).unwrap();
+        assert_eq!(pos.get_state(white), PointState::Empty);
+        assert_eq!(Position9x13::from_string(".............").err(), Some(BoardError::MalformedDiagram { row: 1, column: 10 }));
+        assert_eq!(Position5x7::from_string(".....\n.....\n.....\n.....\n.....\n.....\n.......").err(),
+                   Some(BoardError::MalformedDiagram { row: 7, column: 6 }));
+        assert_eq!(Position5x7::from_string(".....\n.....\n.....\n.....\n.....\n.....\n.....\n.....").err(),
+                   Some(BoardError::MalformedDiagram { row: 8, column: 1 }));
+        assert!(rollout::<Position5x7>().0 < 1000);
+        assert!(rollout::<Position9x13>().0 < 1000);
+    }
+
+    #[test]
+    fn test_from_string_9() {
+        let pos = Position9::from_string("\
+.X.O.....
+XX.OO....
+...O.....
+OO.O.....
+.........
+.........
+.........
+.........
+.........").unwrap();
+        assert_eq!(pos.get_state(pos.xy_to_linear(2, 1)), PointState::Black);
+        assert_eq!(pos.get_state(pos.xy_to_linear(4, 4)), PointState::White);
+        assert!(pos.check_legal());
+        // 黒は石3個と眼1個、白は石7個、コミ6.5
+        assert_eq!(pos.score(), 4.0 - 7.0 - 6.5);
+        assert_eq!(Position9::from_string(".........").err(), Some(BoardError::MalformedDiagram { row: 2, column: 1 }));
+        assert_eq!(Position9::from_string("....\n..X#.....").err(), Some(BoardError::MalformedDiagram { row: 1, column: 5 }));
+        assert_eq!(Position9::from_string(".........\n..X#.....").err(), Some(BoardError::MalformedDiagram { row: 2, column: 4 }));
+        assert!(Position13::from_string(".........").is_err());
     }
 
     use test::Bencher;
     #[bench]
     fn bench_rollout(b: &mut Bencher) {
-        b.iter(rollout);
+        b.iter(rollout::<Position19>);
+    }
+
+    #[bench]
+    fn bench_rollout_9(b: &mut Bencher) {
+        b.iter(rollout::<Position9>);
     }
 }
@@ -0,0 +1,129 @@
+use rand::Rng;
+use go_board::*;
+use rule::*;
+use eye::*;
+use seki::*;
+use semeai::*;
+
+/// プレイアウトの設定です。
+#[derive(Clone, Debug)]
+pub struct PlayoutConfig {
+    /// 最大手数。これを超えると終局していなくても打ち切ります。
+    pub max_moves: usize,
+    /// trueなら着手の列を記録します。
+    pub record_moves: bool,
+    /// trueなら眼形の急所があればランダムな手の代わりに打ちます。
+    pub vital_points: bool,
+    /// trueなら先に打てば勝てる攻め合いがあればランダムな手の代わりに打ちます。
+    pub semeai_moves: bool,
+    /// trueならランダムな手でセキの共有の呼吸点に打たず、セキを壊しません。
+    pub keep_seki: bool,
+}
+
+impl Default for PlayoutConfig {
+    fn default() -> Self {
+        PlayoutConfig {
+            max_moves: 1000,
+            record_moves: false,
+            vital_points: false,
+            semeai_moves: false,
+            keep_seki: false,
+        }
+    }
+}
+
+/// プレイアウトの結果です。
+#[derive(Clone, Debug)]
+pub struct PlayoutResult<T: Rule> {
+    /// 最終局面のスコア(黒から見た値)
+    pub score: f32,
+    /// 手数(パスも含みます)
+    pub num_moves: usize,
+    /// 終局したならtrue、最大手数で打ち切ったならfalseです。
+    pub finished: bool,
+    /// 最終局面
+    pub position: T,
+    /// 着手の列。PlayoutConfig::record_movesがtrueのときだけSomeです。
+    pub moves: Option<Vec<Move>>,
+}
+
+/// 自分の眼を潰さない合法手をランダムに選んで着手します。そのような手がなければパスします。
+/// 打った手を返します。
+pub fn play_random_move<T: Rule, R: Rng>(position: &mut T, rng: &mut R) -> Move {
+    random_move(position, rng, false)
+}
+
+/// play_random_moveと同じですが、セキの共有の呼吸点(is_seki_libertyの点)には打ちません。
+pub fn play_random_move_keeping_seki<T: Rule, R: Rng>(position: &mut T, rng: &mut R) -> Move {
+    random_move(position, rng, true)
+}
+
+/// ランダムな手を着手します。keep_sekiがtrueならセキの共有の呼吸点を除きます。
+fn random_move<T: Rule, R: Rng>(position: &mut T, rng: &mut R, keep_seki: bool) -> Move {
+    let candidates = position.empties();
+    if !candidates.is_empty() {
+        let start_index = rng.gen_range(0, candidates.len());
+        for i in (start_index..candidates.len()).chain(0..start_index) {
+            let pt = candidates[i];
+            if position.is_eye(pt) != position.get_turn().to_pointstate() && !(keep_seki && is_seki_liberty(position, pt))
+                && position.play(Move::Linear(pt)).is_ok() {
+                return Move::Linear(pt);
+            }
+        }
+    }
+    position.play(Move::Pass).expect("pass is always legal");
+    Move::Pass
+}
+
+/// 盤上の眼形の急所のうち、最初に見つかった合法手を着手します。
+/// 急所に打つと自分の眼形は二眼に、相手の眼形は一眼になります。急所の合法手がなければ何もせずNoneを返します。
+pub fn play_vital_point<T: Rule>(position: &mut T) -> Option<Move> {
+    for (_, _, eye_space) in eye_spaces(position, 6) {
+        if let (Eyes::Unsettled, Some(pt)) = (eye_space.eyes(), eye_space.vital) {
+            if position.play(Move::Linear(pt)).is_ok() {
+                return Some(Move::Linear(pt));
+            }
+        }
+    }
+    None
+}
+
+/// 手番の側が先に打てば勝てる攻め合いの着手を打ちます。urgent_semeai_moveを参照してください。
+/// そのような手がないか打てなければ何もせずNoneを返します。
+pub fn play_semeai_move<T: Rule>(position: &mut T) -> Option<Move> {
+    let mov = Move::Linear(urgent_semeai_move(position)?);
+    position.play(mov).ok().map(|_| mov)
+}
+
+/// 局面positionからplay_random_moveで終局(2連続パス)まで打ち進めます。
+/// 局面の連続したパスの回数から数えるので、パスの直後の局面からも正しく終局します。
+pub fn playout<T: Rule, R: Rng>(mut position: T, config: &PlayoutConfig, rng: &mut R) -> PlayoutResult<T> {
+    let mut moves = if config.record_moves { Some(Vec::new()) } else { None };
+    let mut num_moves = 0;
+
+    while !position.is_game_over() && num_moves < config.max_moves {
+        let mut mov = None;
+        if config.vital_points {
+            mov = play_vital_point(&mut position);
+        }
+        if config.semeai_moves && mov.is_none() {
+            mov = play_semeai_move(&mut position);
+        }
+        let mov = match mov {
+            Some(mov) => mov,
+            None if config.keep_seki => play_random_move_keeping_seki(&mut position, rng),
+            None => play_random_move(&mut position, rng),
+        };
+        if let Some(ref mut moves) = moves {
+            moves.push(mov);
+        }
+        num_moves += 1;
+    }
+    PlayoutResult {
+        score: position.score(),
+        num_moves,
+        finished: position.is_game_over(),
+        position,
+        moves,
+    }
+}
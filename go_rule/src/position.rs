@@ -1,24 +1,28 @@
 use std::fmt;
-use std::mem::uninitialized;
+use std::cell::RefCell;
+use arrayvec::ArrayVec;
 use go_board::*;
 use rule::*;
+use history::*;
+use zobrist::*;
+use ruleset::*;
 
 /// 盤上の状況を表す構造体PositionXX(XXは盤サイズ)を宣言するマクロです。
-/// $nameが構造体名、$sizeは碁盤のサイズ, $arrayは配列サイズの定数名です。
+/// $nameが構造体名、$width, $heightは碁盤の幅と高さ, $arrayは配列サイズの定数名です。
 macro_rules! make_position {
-    ($size:expr, $ob_size:expr, $name:ident, $array:ident, $marker:ident, $marker_instance:ident) => {
-        const $array: usize = array_size!($size, $ob_size);
+    ($width:expr, $height:expr, $ob_size:expr, $name:ident, $array:ident, $marker:ident, $marker_instance:ident) => {
+        const $array: usize = array_size!($width, $height, $ob_size);
         make_marker!($marker, $array);
 
-        /// 共有Markerインスタンスです。
-        pub static mut $marker_instance: $marker = $marker {
-            value: 0,
-            marks: [0; $array],
-        };
+        thread_local! {
+            /// スレッドごとのMarkerインスタンスです。
+            /// 局面自体はMarkerを持たないので、複数のスレッドで別々の局面を同時に扱えます。
+            static $marker_instance: RefCell<$marker> = RefCell::new($marker::new());
+        }
 
         /// 盤上の局面を表す構造体です。
         #[allow(dead_code)]
-        #[derive(Copy)]
+        #[derive(Clone)]
         pub struct $name {
             /// コミ
             komi: f32,
@@ -28,12 +32,28 @@ macro_rules! make_position {
             turn: Color,
             /// コウによる着手禁止点
             ko: Option<LinearCoord>,
-        }
-
-        impl Clone for $name {
-            fn clone(&self) -> Self {
-                *self
-            }
+            /// ルール
+            rule_set: RuleSet,
+            /// スーパーコウ判定のための局面履歴
+            history: History,
+            /// 局面のZobristハッシュ値
+            hash: u64,
+            /// 連続したパスの回数
+            consecutive_passes: u32,
+            /// 投了した手番
+            resigned: Option<Color>,
+            /// 黒が取った白石の数
+            black_prisoners: u32,
+            /// 白が取った黒石の数
+            white_prisoners: u32,
+            /// 石のある点ごとの連の番号。連の石の1つの線形座標です。空点や盤外の値は意味を持ちません。
+            chain_ids: [LinearCoord; $array],
+            /// 連の石をつなぐ巡回リストの、次の石の線形座標
+            next_stones: [LinearCoord; $array],
+            /// 連の番号ごとの石の数
+            chain_sizes: [u16; $array],
+            /// 連の番号ごとの呼吸点の数
+            chain_liberties: [u16; $array],
         }
 
         impl fmt::Display for $name {
@@ -45,12 +65,12 @@ macro_rules! make_position {
         impl Board for $name {
             #[inline]
             fn get_width(&self) -> LinearCoord {
-                $size
+                $width
             }
 
             #[inline]
             fn get_height(&self) -> LinearCoord {
-                $size
+                $height
             }
 
             #[inline]
@@ -65,11 +85,30 @@ macro_rules! make_position {
                 }
             }
 
-            #[inline]
+            /// 連の情報も差分更新します。石を取り除くと連が分かれることがあるので、残りの石の連を作り直します。
             fn set_state(&mut self, pt: LinearCoord, value: PointState) {
-                unsafe {
-                    let elem = self.states.get_unchecked_mut(pt as usize);
-                    *elem = value;
+                let old = self.get_state(pt);
+                if old == value {
+                    return;
+                }
+                let old_id = self.chain_ids[pt as usize];
+                self.write_state(pt, value);
+                if old == PointState::Empty {
+                    for id in self.adjacent_chains(pt, |_| true) {
+                        self.chain_liberties[id as usize] -= 1;
+                    }
+                }
+                if old.is_stone() {
+                    self.split_chain(pt, old_id, old);
+                }
+                if value == PointState::Empty {
+                    // 作り直した連は呼吸点を数え直しているので除きます。
+                    for id in self.adjacent_chains(pt, |state| state != old) {
+                        self.chain_liberties[id as usize] += 1;
+                    }
+                }
+                if value.is_stone() {
+                    self.add_stone(pt);
                 }
             }
 
@@ -80,6 +119,7 @@ macro_rules! make_position {
 
             #[inline]
             fn set_turn(&mut self, value: Color) {
+                self.hash ^= turn_key(self.turn) ^ turn_key(value);
                 self.turn = value;
             }
         }
@@ -110,48 +150,162 @@ macro_rules! make_position {
 
             #[inline]
             fn set_ko(&mut self, pt: Option<LinearCoord>) {
+                self.hash ^= ko_key(self.ko) ^ ko_key(pt);
                 self.ko = pt;
             }
 
+            #[inline]
+            fn get_hash(&self) -> u64 {
+                self.hash
+            }
+
+            #[inline]
+            fn get_rule_set(&self) -> RuleSet {
+                self.rule_set
+            }
+
+            #[inline]
+            fn set_rule_set(&mut self, value: RuleSet) {
+                self.rule_set = value;
+            }
+
+            #[inline]
+            fn get_consecutive_passes(&self) -> u32 {
+                self.consecutive_passes
+            }
+
+            #[inline]
+            fn set_consecutive_passes(&mut self, value: u32) {
+                self.consecutive_passes = value;
+            }
+
+            #[inline]
+            fn get_resigned(&self) -> Option<Color> {
+                self.resigned
+            }
+
+            #[inline]
+            fn set_resigned(&mut self, value: Option<Color>) {
+                self.resigned = value;
+            }
+
+            #[inline]
+            fn get_prisoners(&self, color: Color) -> u32 {
+                match color {
+                    Color::Black => self.black_prisoners,
+                    Color::White => self.white_prisoners,
+                }
+            }
+
+            #[inline]
+            fn set_prisoners(&mut self, color: Color, value: u32) {
+                match color {
+                    Color::Black => self.black_prisoners = value,
+                    Color::White => self.white_prisoners = value,
+                }
+            }
+
+            #[inline]
+            fn get_history(&self) -> &History {
+                &self.history
+            }
+
+            #[inline]
+            fn get_history_mut(&mut self) -> &mut History {
+                &mut self.history
+            }
+
+            #[inline]
+            fn num_liberties_at(&self, pt: LinearCoord) -> usize {
+                debug_assert!(self.get_state(pt).is_stone(), "no stones");
+                self.chain_liberties[self.chain_ids[pt as usize] as usize] as usize
+            }
+
+            #[inline]
+            fn string_size_at(&self, pt: LinearCoord) -> usize {
+                debug_assert!(self.get_state(pt).is_stone(), "no stones");
+                self.chain_sizes[self.chain_ids[pt as usize] as usize] as usize
+            }
+
+            /// 連全体を取り上げるときは、石を1つずつ取り除いて連を作り直すことをせずにまとめて取り除きます。
+            fn remove_string(&mut self, string: &GoString) {
+                let whole = string.points.first().map_or(false, |&pt| {
+                    self.get_state(pt).is_stone() && self.string_size_at(pt) == string.points.len()
+                });
+                if !whole {
+                    for &e in &string.points {
+                        self.set_state(e, PointState::Empty);
+                    }
+                    return;
+                }
+                for &e in &string.points {
+                    self.write_state(e, PointState::Empty);
+                }
+                for &e in &string.points {
+                    for id in self.adjacent_chains(e, |_| true) {
+                        self.chain_liberties[id as usize] += 1;
+                    }
+                }
+            }
+
             fn string_at(&self, pt: LinearCoord, string: &mut GoString) {
                 debug_assert!(self.is_on_board(pt), "pt = {}", pt);
                 let stone = self.get_state(pt);
                 debug_assert!(stone.is_stone(), "no stones");
 
-                unsafe {
-                    $marker_instance.clear();
+                $marker_instance.with(|marker| {
+                    let mut marker = marker.borrow_mut();
+                    marker.clear();
 
+                    // 同じ石を重複して追加しないよう、配列に追加する時点でマークします。
+                    marker.mark(pt as usize);
                     string.points.push(pt);
                     let mut index = 0;
                     while index < string.points.len() {
                         let pt = string.points[index];
-                        let upt = pt as usize;
-                        if !$marker_instance.is_marked(upt) {
-                            $marker_instance.mark(upt);
-                            for &a in &self.adjacencies_at(pt) {
-                                let ua = a as usize;
-                                if !$marker_instance.is_marked(ua) {
-                                    let state = self.get_state(a);
-                                    if state == stone {
-                                        string.points.push(a);
-                                    } else {
-                                        $marker_instance.mark(ua);
-                                        if state == PointState::Empty {
-                                            string.liberties.push(a);
-                                        }
-                                    }
+                        for &a in &self.adjacencies_at(pt) {
+                            let ua = a as usize;
+                            if !marker.is_marked(ua) {
+                                marker.mark(ua);
+                                let state = self.get_state(a);
+                                if state == stone {
+                                    string.points.push(a);
+                                } else if state == PointState::Empty {
+                                    string.liberties.push(a);
                                 }
                             }
                         }
                         index += 1;
                     }
-                }
+                });
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name::new()
             }
         }
 
         impl $name {
-             pub fn new() -> Self {
-                let mut pos: Self = unsafe { uninitialized() };
+            pub fn new() -> Self {
+                let mut pos = $name {
+                    komi: 0.0,
+                    states: [PointState::Out; $array],
+                    turn: Color::Black,
+                    ko: None,
+                    rule_set: RuleSet::default(),
+                    history: History::new(),
+                    hash: 0,
+                    consecutive_passes: 0,
+                    resigned: None,
+                    black_prisoners: 0,
+                    white_prisoners: 0,
+                    chain_ids: [0; $array],
+                    next_stones: [0; $array],
+                    chain_sizes: [0; $array],
+                    chain_liberties: [0; $array],
+                };
                 pos.reset();
                 pos
             }
@@ -168,11 +322,161 @@ macro_rules! make_position {
                     }
                 }
                 self.set_ko(None);
+                self.history.clear();
+                self.consecutive_passes = 0;
+                self.resigned = None;
+                self.black_prisoners = 0;
+                self.white_prisoners = 0;
                 self.set_turn(Color::Black);
-                self.set_komi(6.5);
+                self.rule_set = RuleSet::default();
+                self.set_komi(self.rule_set.komi);
+            }
+
+            /// 連の情報を更新せずに線形座標ptの状態を設定します。
+            #[inline]
+            fn write_state(&mut self, pt: LinearCoord, value: PointState) {
+                unsafe {
+                    let elem = self.states.get_unchecked_mut(pt as usize);
+                    self.hash ^= point_key(pt, *elem) ^ point_key(pt, value);
+                    *elem = value;
+                }
+            }
+
+            /// 線形座標ptに隣接する石のうち、状態がfilterを満たす石の連の番号を重複なしで返します。
+            #[inline]
+            fn adjacent_chains<F: Fn(PointState) -> bool>(&self, pt: LinearCoord, filter: F) -> ArrayVec<[LinearCoord; 4]> {
+                let mut ids = ArrayVec::new();
+                for &a in &self.adjacencies_at(pt) {
+                    let state = self.get_state(a);
+                    if state.is_stone() && filter(state) {
+                        let id = self.chain_ids[a as usize];
+                        if !ids.contains(&id) {
+                            ids.push(id);
+                        }
+                    }
+                }
+                ids
+            }
+
+            /// 線形座標ptに置いた石を、隣接する自分の連に加えるか新しい連にします。
+            /// 隣接する連の呼吸点からptはすでに除いてある必要があります。
+            fn add_stone(&mut self, pt: LinearCoord) {
+                let stone = self.get_state(pt);
+                let ids = self.adjacent_chains(pt, |state| state == stone);
+                let upt = pt as usize;
+                match ids.len() {
+                    0 => {
+                        self.chain_ids[upt] = pt;
+                        self.next_stones[upt] = pt;
+                        self.chain_sizes[upt] = 1;
+                        self.chain_liberties[upt] = self.adjacencies_at(pt).iter()
+                            .filter(|&&a| self.get_state(a) == PointState::Empty)
+                            .count() as u16;
+                    },
+                    1 => {
+                        let id = ids[0];
+                        self.link_stone(id, pt);
+                        // 連の他の石に接していない空点が新しい呼吸点です。
+                        for &a in &self.adjacencies_at(pt) {
+                            if self.get_state(a) == PointState::Empty && !self.adjacencies_at(a).iter()
+                                .any(|&b| b != pt && self.get_state(b) == stone && self.chain_ids[b as usize] == id) {
+                                self.chain_liberties[id as usize] += 1;
+                            }
+                        }
+                    },
+                    _ => {
+                        // 一番大きな連に他の連をつなぎ、呼吸点を数え直します。
+                        let id = *ids.iter().max_by_key(|&&id| self.chain_sizes[id as usize]).unwrap();
+                        for &other in ids.iter().filter(|&&other| other != id) {
+                            let mut e = other;
+                            loop {
+                                self.chain_ids[e as usize] = id;
+                                e = self.next_stones[e as usize];
+                                if e == other {
+                                    break;
+                                }
+                            }
+                            self.next_stones.swap(id as usize, other as usize);
+                            self.chain_sizes[id as usize] += self.chain_sizes[other as usize];
+                        }
+                        self.link_stone(id, pt);
+                        self.count_liberties(id);
+                    },
+                }
+            }
+
+            /// 番号idの連に線形座標ptの石を加えます。呼吸点の数は変えません。
+            #[inline]
+            fn link_stone(&mut self, id: LinearCoord, pt: LinearCoord) {
+                self.chain_ids[pt as usize] = id;
+                self.next_stones[pt as usize] = self.next_stones[id as usize];
+                self.next_stones[id as usize] = pt;
+                self.chain_sizes[id as usize] += 1;
+            }
+
+            /// 色stoneの石を取り除いた線形座標ptが属していた番号idの連を、残った石で作り直します。
+            fn split_chain(&mut self, pt: LinearCoord, id: LinearCoord, stone: PointState) {
+                if self.chain_sizes[id as usize] == 1 {
+                    return;
+                }
+                // 残った石の連の番号を、どの連にも使わない盤外の点0にしてから、ptの隣の石ごとにたどり直します。
+                let mut e = id;
+                loop {
+                    self.chain_ids[e as usize] = 0;
+                    e = self.next_stones[e as usize];
+                    if e == id {
+                        break;
+                    }
+                }
+                let mut stack = LinearCoordVec::new();
+                for &a in &self.adjacencies_at(pt) {
+                    if self.get_state(a) != stone || self.chain_ids[a as usize] != 0 {
+                        continue;
+                    }
+                    self.chain_ids[a as usize] = a;
+                    self.next_stones[a as usize] = a;
+                    self.chain_sizes[a as usize] = 1;
+                    stack.push(a);
+                    while let Some(e) = stack.pop() {
+                        for &b in &self.adjacencies_at(e) {
+                            if self.get_state(b) == stone && self.chain_ids[b as usize] == 0 {
+                                self.link_stone(a, b);
+                                stack.push(b);
+                            }
+                        }
+                    }
+                    self.count_liberties(a);
+                }
+            }
+
+            /// 番号idの連の呼吸点を数え直します。
+            fn count_liberties(&mut self, id: LinearCoord) {
+                let count = $marker_instance.with(|marker| {
+                    let mut marker = marker.borrow_mut();
+                    marker.clear();
+                    let mut count = 0;
+                    let mut e = id;
+                    loop {
+                        for &a in &self.adjacencies_at(e) {
+                            if self.get_state(a) == PointState::Empty && !marker.is_marked(a as usize) {
+                                marker.mark(a as usize);
+                                count += 1;
+                            }
+                        }
+                        e = self.next_stones[e as usize];
+                        if e == id {
+                            break;
+                        }
+                    }
+                    count
+                });
+                self.chain_liberties[id as usize] = count;
             }
 
             /// 盤上の文字表現から$nameのインスタンスを返します。
+            /// 行数が高さ、各行の文字数が幅に一致する必要があります。
+            /// 使える文字は空点'.'、黒'X'、白'O'です。
+            /// 不正な文字表現はBoardError::MalformedDiagramになります。
             /// 以下は盤上の文字表現は4路盤の例です。
             ///
             /// ```text
@@ -181,21 +485,32 @@ macro_rules! make_position {
             /// .O..
             /// ....
             /// ```
-            pub fn from_string(s: &str) -> Result<Self, &str> {
+            pub fn from_string(s: &str) -> Result<Self, BoardError> {
                 let mut pos = Self::new();
-                if s.lines().count() != pos.get_height() as usize {
-                    return Err("wrong rows");
-                }
-                let lines = s.lines();
-                let width = pos.get_width();
-                for (y, line) in lines.enumerate() {
-                    if line.len() != width as usize {
-                        return Err("wrong columns");
+                let width = pos.get_width() as usize;
+                let height = pos.get_height() as usize;
+                let mut rows = 0;
+                for (y, line) in s.lines().enumerate() {
+                    if y >= height {
+                        return Err(BoardError::MalformedDiagram { row: y + 1, column: 1 });
                     }
+                    let mut columns = 0;
                     for (x, c) in line.chars().enumerate() {
+                        let state = match c {
+                            '.' | 'X' | 'O' if x < width => PointState::from_char(c),
+                            _ => return Err(BoardError::MalformedDiagram { row: y + 1, column: x + 1 }),
+                        };
                         let i = pos.xy_to_linear(x as u8 + 1, y as u8 + 1);
-                        pos.set_state(i, PointState::from_char(c));
+                        pos.set_state(i, state);
+                        columns += 1;
+                    }
+                    if columns != width {
+                        return Err(BoardError::MalformedDiagram { row: y + 1, column: columns + 1 });
                     }
+                    rows += 1;
+                }
+                if rows != height {
+                    return Err(BoardError::MalformedDiagram { row: rows + 1, column: 1 });
                 }
                 Ok(pos)
             }
@@ -203,6 +518,13 @@ macro_rules! make_position {
     }
 }
 
-// マクロを使って19路盤のstructを定義します。
+// マクロを使って9路盤から25路盤までと、研究用の長方形の盤(幅x高さ)のstructを定義します。
 // Rust(1.20.0)では識別子を合成して定義に使うことができないので、必要な識別子を引数に与えています。
-make_position!(19, 1, Position19, ARRAY_SIZE_19, Marker19, MARKER19);
+make_position!(9, 9, 1, Position9, ARRAY_SIZE_9, Marker9, MARKER9);
+make_position!(13, 13, 1, Position13, ARRAY_SIZE_13, Marker13, MARKER13);
+make_position!(19, 19, 1, Position19, ARRAY_SIZE_19, Marker19, MARKER19);
+make_position!(21, 21, 1, Position21, ARRAY_SIZE_21, Marker21, MARKER21);
+make_position!(23, 23, 1, Position23, ARRAY_SIZE_23, Marker23, MARKER23);
+make_position!(25, 25, 1, Position25, ARRAY_SIZE_25, Marker25, MARKER25);
+make_position!(5, 7, 1, Position5x7, ARRAY_SIZE_5X7, Marker5x7, MARKER5X7);
+make_position!(9, 13, 1, Position9x13, ARRAY_SIZE_9X13, Marker9x13, MARKER9X13);
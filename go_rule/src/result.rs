@@ -0,0 +1,105 @@
+use std::fmt;
+use go_board::Color;
+
+/// 勝ちの理由です。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WinReason {
+    /// 目数(勝った側から見た正の値)での勝ちです。
+    Score(f32),
+    /// 中押し勝ちです。
+    Resign,
+    /// 時間切れ勝ちです。
+    Time,
+    /// 反則勝ちです。
+    Forfeit,
+    /// 理由の記録がない勝ちです。
+    Unknown,
+}
+
+/// 対局の結果です。
+///
+/// DisplayはSGFのREプロパティの形式(B+R, W+3.5, 0, Void, ?)で表示します。
+/// GTPのfinal_scoreの形式とも一致します。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameResult {
+    /// 勝った側と理由です。
+    Win(Color, WinReason),
+    /// 持碁(引き分け)です。
+    Jigo,
+    /// 無勝負です。
+    Void,
+    /// 結果不明です。
+    Unknown,
+}
+
+impl GameResult {
+    /// 黒から見たスコアscoreの結果を返します。
+    pub fn from_score(score: f32) -> Self {
+        if score > 0.0 {
+            GameResult::Win(Color::Black, WinReason::Score(score))
+        } else if score < 0.0 {
+            GameResult::Win(Color::White, WinReason::Score(-score))
+        } else {
+            GameResult::Jigo
+        }
+    }
+
+    /// 勝った側を返します。勝ち負けがなければNoneです。
+    pub fn winner(&self) -> Option<Color> {
+        match *self {
+            GameResult::Win(color, _) => Some(color),
+            _ => None,
+        }
+    }
+
+    /// SGFのREプロパティの値を解析します。解釈できなければNoneを返します。
+    ///
+    /// "B+Resign", "W+Time", "Draw"などの長い表記も受け付けます。
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "0" | "Draw" | "Jigo" => return Some(GameResult::Jigo),
+            "Void" => return Some(GameResult::Void),
+            "?" => return Some(GameResult::Unknown),
+            _ => {},
+        }
+        let color = match s.get(0..2) {
+            Some("B+") => Color::Black,
+            Some("W+") => Color::White,
+            _ => return None,
+        };
+        let reason = match &s[2..] {
+            "" => WinReason::Unknown,
+            "R" | "Resign" => WinReason::Resign,
+            "T" | "Time" => WinReason::Time,
+            "F" | "Forfeit" => WinReason::Forfeit,
+            score => match score.parse::<f32>() {
+                Ok(score) if score > 0.0 => WinReason::Score(score),
+                _ => return None,
+            },
+        };
+        Some(GameResult::Win(color, reason))
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GameResult::Win(color, reason) => {
+                write!(f, "{}+", match color {
+                    Color::Black => "B",
+                    Color::White => "W",
+                })?;
+                match reason {
+                    WinReason::Score(score) => write!(f, "{}", score),
+                    WinReason::Resign => write!(f, "R"),
+                    WinReason::Time => write!(f, "T"),
+                    WinReason::Forfeit => write!(f, "F"),
+                    WinReason::Unknown => Ok(()),
+                }
+            },
+            GameResult::Jigo => write!(f, "0"),
+            GameResult::Void => write!(f, "Void"),
+            GameResult::Unknown => write!(f, "?"),
+        }
+    }
+}
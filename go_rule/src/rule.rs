@@ -1,21 +1,57 @@
 use arrayvec::ArrayVec;
 use go_board::*;
+use history::*;
+use zobrist::*;
+use error::*;
+use result::*;
+use score::*;
+use ruleset::*;
+use eye::*;
+use seki::*;
 
 /// 着手のundoのための情報を保持する構造体です。
 pub struct MoveLog {
+    /// 着手した手番
     turn: Color,
     ko: Option<LinearCoord>,
     mov: Move,
+    /// 取り上げた石。自殺手の場合は自分の石です。
     captives: LinearCoordVec,
+    /// 複数の石の自殺手ならtrue
+    suicide: bool,
+    /// 着手前の局面履歴の長さ
+    history_len: usize,
+    /// 着手前の連続したパスの回数
+    passes: u32,
+    /// 着手前の黒と白のアゲハマの数
+    prisoners: (u32, u32),
+}
+
+impl MoveLog {
+    /// 着手した手番を返します。
+    pub fn get_turn(&self) -> Color {
+        self.turn
+    }
+
+    /// 記録された着手を返します。
+    pub fn get_move(&self) -> Move {
+        self.mov
+    }
 }
 
 /// つながった石「連(String)」を表す構造体です。
-// TODO - インスタンス1つで4 * 384 * 3 = 4.6kB消費するのでなんとかしたほうがいいかもしれない。
+// TODO - インスタンス1つで2 * 768 * 2 = 3kB消費するのでなんとかしたほうがいいかもしれない。
 pub struct GoString {
     pub points: LinearCoordVec,
     pub liberties: LinearCoordVec,
 }
 
+impl Default for GoString {
+    fn default() -> Self {
+        GoString::new()
+    }
+}
+
 impl GoString {
     pub fn new() -> GoString {
         GoString {
@@ -32,8 +68,9 @@ impl GoString {
         self.liberties.len()
     }
 
-    pub fn is_nakade_shape(&self) -> bool {
-        unimplemented!()
+    /// 連が中手の形ならtrueを返します。この連が取られると、取った側の眼は一眼にしかなりません。
+    pub fn is_nakade_shape<T: Board + ?Sized>(&self, board: &T) -> bool {
+        classify_eye_space(board, &self.points).shape.is_nakade()
     }
 }
 
@@ -55,6 +92,93 @@ pub trait Rule : Board {
     /// コウによる着手禁止点を設定します。
     fn set_ko(&mut self, pt: Option<LinearCoord>);
 
+    /// ルールを返します。
+    fn get_rule_set(&self) -> RuleSet;
+
+    /// ルールを設定します。コミは変わりません。
+    fn set_rule_set(&mut self, value: RuleSet);
+
+    /// コウのルールを返します。
+    #[inline]
+    fn get_ko_rule(&self) -> KoRule {
+        self.get_rule_set().ko_rule
+    }
+
+    /// コウのルールを設定します。
+    fn set_ko_rule(&mut self, value: KoRule) {
+        let mut rule_set = self.get_rule_set();
+        rule_set.ko_rule = value;
+        self.set_rule_set(rule_set);
+    }
+
+    /// 連続したパスの回数を返します。
+    fn get_consecutive_passes(&self) -> u32;
+
+    /// 連続したパスの回数を設定します。
+    fn set_consecutive_passes(&mut self, value: u32);
+
+    /// 投了した手番を返します。投了していなければNoneです。
+    fn get_resigned(&self) -> Option<Color>;
+
+    /// 投了した手番を設定します。
+    fn set_resigned(&mut self, value: Option<Color>);
+
+    /// 手番colorが取った石(アゲハマ)の数を返します。
+    fn get_prisoners(&self, color: Color) -> u32;
+
+    /// 手番colorが取った石(アゲハマ)の数を設定します。
+    fn set_prisoners(&mut self, color: Color, value: u32);
+
+    /// 終局(2連続パスか投了)しているか調べます。
+    ///
+    /// ルールがパス石を使うなら、白が最後にパスした2連続パスで終局します。
+    #[inline]
+    fn is_game_over(&self) -> bool {
+        self.is_ended_by_passes() || self.get_resigned().is_some()
+    }
+
+    /// 連続したパスで終局しているか調べます。is_game_overを参照してください。
+    #[inline]
+    fn is_ended_by_passes(&self) -> bool {
+        self.get_consecutive_passes() >= 2 && (!self.get_rule_set().pass_stones || self.get_turn() == Color::Black)
+    }
+
+    /// 終局していれば結果を返します。2連続パスの場合はrule_scoreで判定します。
+    fn get_result(&self) -> Option<GameResult> {
+        if let Some(color) = self.get_resigned() {
+            Some(GameResult::Win(color.opponent(), WinReason::Resign))
+        } else if self.is_ended_by_passes() {
+            Some(GameResult::from_score(self.rule_score(&[])))
+        } else {
+            None
+        }
+    }
+
+    /// スーパーコウ判定のための局面履歴を返します。
+    fn get_history(&self) -> &History;
+
+    /// スーパーコウ判定のための局面履歴を返します。
+    fn get_history_mut(&mut self) -> &mut History;
+
+    /// 局面(石の配置、手番、コウによる着手禁止点)のZobristハッシュ値を返します。
+    ///
+    /// set_state, set_turn, set_koで差分更新される値です。
+    fn get_hash(&self) -> u64;
+
+    /// 石の配置だけのZobristハッシュ値を返します。
+    #[inline]
+    fn get_board_hash(&self) -> u64 {
+        self.get_hash() ^ turn_key(self.get_turn()) ^ ko_key(self.get_ko())
+    }
+
+    /// 局面のZobristハッシュ値を盤上を走査して計算します。
+    /// 差分更新されたget_hashの値の検証用です。
+    fn compute_hash(&self) -> u64 {
+        self.all_points().fold(turn_key(self.get_turn()) ^ ko_key(self.get_ko()), |hash, pt| {
+            hash ^ point_key(pt, self.get_state(pt))
+        })
+    }
+
     /// 盤上が正常な局面かチェックします。
     fn check_legal(&self) -> bool {
         for pt in self.all_points() {
@@ -74,36 +198,99 @@ pub trait Rule : Board {
     #[inline]
     fn adjacencies_at(&self, pt: LinearCoord) -> [LinearCoord; 4] {
         debug_assert!(self.is_on_board(pt), "pt = {}", pt);
-        let width_plus_2 = self.get_width() + 2;
+        let stride = self.get_width_with_ob();
         // North East South  West
-        [pt - width_plus_2, pt + 1, pt + width_plus_2, pt - 1]
+        [pt - stride, pt + 1, pt + stride, pt - 1]
     }
 
     /// 線形座標ptの点を含む連を返します。
     ///
-    /// Markerインスタンスを使った実装を想定しているので、デフォルト実装がありません。
+    /// スレッドごとのMarkerインスタンスを使った実装を想定しているので、デフォルト実装がありません。
     /// 実装はposition.rsを参照してください。
     fn string_at(&self, pt: LinearCoord, string: &mut GoString);
 
+    /// 線形座標ptの石を含む連の呼吸点の数を返します。
+    ///
+    /// デフォルト実装はstring_atで数えます。連を差分更新する実装ではstring_atを呼ばずに返せます。
+    fn num_liberties_at(&self, pt: LinearCoord) -> usize {
+        let mut string = GoString::new();
+        self.string_at(pt, &mut string);
+        string.num_liberties()
+    }
+
+    /// 線形座標ptの石を含む連の石の数を返します。num_liberties_atを参照してください。
+    fn string_size_at(&self, pt: LinearCoord) -> usize {
+        let mut string = GoString::new();
+        self.string_at(pt, &mut string);
+        string.size()
+    }
+
     /// 着手します。
     ///
-    /// 成功するとMoveLogを返します。失敗するとエラーメッセージを返します。
-    fn play(&mut self, mov: Move) -> Result<MoveLog, &'static str> {
+    /// 成功するとMoveLogを返します。失敗すると非合法の理由をRuleErrorで返します。
+    /// コウのルールがスーパーコウの場合、過去の局面を再現する着手はRuleError::Superkoで失敗します。
+    /// ルールが複数の石の自殺手を認めていれば、自殺手で自分の連を取り上げます。
+    /// ルールがパス石を使うなら、パスすると相手のアゲハマが1つ増えます。
+    /// 投了すると終局し、それ以降の着手はRuleError::GameOverで失敗します。
+    /// 2連続パスの後も着手でき、連続したパスの回数は0に戻ります。
+    fn play(&mut self, mov: Move) -> Result<MoveLog, RuleError> {
+        if self.get_resigned().is_some() {
+            return Err(RuleError::GameOver);
+        }
+        let passes = self.get_consecutive_passes();
+        let prisoners = (self.get_prisoners(Color::Black), self.get_prisoners(Color::White));
+        let rule_set = self.get_rule_set();
         let ko = self.get_ko();
+        let history_len = self.get_history().len();
+        let ko_rule = rule_set.ko_rule;
+        // 着手前の局面。途中でコウのルールを変えても使えるように、
+        // 単純なコウのルールでも着手に成功したら履歴に記録します。
+        let (previous_hash, previous_turn) = (self.get_board_hash(), self.get_turn());
         match mov {
             Move::Pass => {
+                self.get_history_mut().push(previous_hash, previous_turn);
+                let turn = self.get_turn();
+                if rule_set.pass_stones {
+                    let opponent_prisoners = self.get_prisoners(turn.opponent()) + 1;
+                    self.set_prisoners(turn.opponent(), opponent_prisoners);
+                }
+                self.set_ko(None);
                 self.switch_turn();
+                self.set_consecutive_passes(passes + 1);
                 Ok(MoveLog {
-                    turn: self.get_turn(),
+                    turn,
                     mov: Move::Pass,
-                    ko: ko,
+                    ko,
+                    captives: LinearCoordVec::new(),
+                    suicide: false,
+                    history_len,
+                    passes,
+                    prisoners,
+                })
+            },
+            Move::Resign => {
+                let turn = self.get_turn();
+                self.set_resigned(Some(turn));
+                Ok(MoveLog {
+                    turn,
+                    mov: Move::Resign,
+                    ko,
                     captives: LinearCoordVec::new(),
+                    suicide: false,
+                    history_len,
+                    passes,
+                    prisoners,
                 })
             },
             Move::Linear(pt) => {
-                debug_assert!(self.is_on_board(pt), "pt = {}", pt);
+                if !self.is_on_board(pt) {
+                    return Err(RuleError::OffBoard);
+                }
+                if self.get_state(pt) != PointState::Empty {
+                    return Err(RuleError::Occupied);
+                }
                 if self.is_ko(pt) {
-                    return Err("prohibit move");
+                    return Err(RuleError::Ko);
                 }
 
                 let turn = self.get_turn();
@@ -113,31 +300,168 @@ pub trait Rule : Board {
                 let mut captives = LinearCoordVec::new();
                 self.capture_by(pt, &mut captives);
                 // 自分のダメヅマリを調べる
-                let mut string = GoString::new();
-                self.string_at(pt, &mut string);
-                let liberties = string.num_liberties();
-                if liberties == 0 { // 着手禁止点なら
-                    // 着手を戻す
-                    self.set_state(pt, PointState::Empty);
-                    return Err("suicide move");
+                let liberties = self.num_liberties_at(pt);
+                let suicide = liberties == 0;
+                if suicide {
+                    if !rule_set.multi_stone_suicide || self.string_size_at(pt) == 1 { // 着手禁止点なら
+                        // 着手を戻す
+                        self.set_state(pt, PointState::Empty);
+                        return Err(RuleError::Suicide);
+                    }
+                    // 自分の連を取り上げる
+                    let mut string = GoString::new();
+                    self.string_at(pt, &mut string);
+                    self.remove_string(&string);
+                    for &e in &string.points {
+                        captives.push(e);
+                    }
+                    let opponent_prisoners = self.get_prisoners(turn.opponent()) + captives.len() as u32;
+                    self.set_prisoners(turn.opponent(), opponent_prisoners);
+                } else {
+                    let turn_prisoners = self.get_prisoners(turn) + captives.len() as u32;
+                    self.set_prisoners(turn, turn_prisoners);
                 }
-                // コウヌキだったかチェック
-                self.set_ko(if captives.len() == 1 && liberties == 1 && string.size() == 1 {
-                    Some(string.liberties[0])
+                // コウヌキだったかチェック。1つの石で1つの石を取ったなら、取った点が唯一の呼吸点です。
+                self.set_ko(if captives.len() == 1 && liberties == 1 && self.string_size_at(pt) == 1 {
+                    Some(captives[0])
                 } else {
                     None
                 });
 
                 self.switch_turn();
-                Ok(MoveLog {
-                    turn: self.get_turn(),
+                let move_log = MoveLog {
+                    turn,
                     mov: Move::Linear(pt),
-                    ko: ko,
-                    captives: captives,
+                    ko,
+                    captives,
+                    suicide,
+                    history_len,
+                    passes,
+                    prisoners,
+                };
+                // 過去の局面の繰り返しかチェック
+                if ko_rule != KoRule::Simple {
+                    let new_hash = self.get_board_hash();
+                    if self.get_history().is_repetition(ko_rule, new_hash, self.get_turn()) {
+                        self.undo_play(&move_log);
+                        return Err(RuleError::Superko);
+                    }
+                }
+                self.get_history_mut().push(previous_hash, previous_turn);
+                self.set_consecutive_passes(0);
+                Ok(move_log)
+            }
+        }
+    }
+
+    /// 手番colorとして着手movを打ちます。
+    ///
+    /// colorが局面の手番でなければRuleError::WrongTurnで失敗し、局面を変えません。それ以外はplayと同じです。
+    /// 手番を問わずに打つには、set_turnで手番を設定してからplayを呼んでください。
+    fn play_as(&mut self, color: Color, mov: Move) -> Result<MoveLog, RuleError> {
+        if color != self.get_turn() {
+            return Err(RuleError::WrongTurn);
+        }
+        self.play(mov)
+    }
+
+    /// 局面を変えずに着手movが合法か調べます。
+    ///
+    /// 合法ならOk(())、非合法ならplayと同じRuleErrorを返します。
+    fn check_move(&self, mov: Move) -> Result<(), RuleError> {
+        if self.get_resigned().is_some() {
+            return Err(RuleError::GameOver);
+        }
+        let pt = match mov {
+            Move::Pass | Move::Resign => return Ok(()),
+            Move::Linear(pt) => pt,
+        };
+        if !self.is_on_board(pt) {
+            return Err(RuleError::OffBoard);
+        }
+        if self.get_state(pt) != PointState::Empty {
+            return Err(RuleError::Occupied);
+        }
+        if self.is_ko(pt) {
+            return Err(RuleError::Ko);
+        }
+
+        let turn = self.get_turn().to_pointstate();
+        let opponent = turn.opponent();
+        // 呼吸点が残るか、相手の石を取れれば自殺手ではありません。
+        let mut has_liberty = false;
+        let mut captives = LinearCoordVec::new();
+        // 呼吸点がptだけの自分の連の石
+        let mut suicides = LinearCoordVec::new();
+        for &a in &self.adjacencies_at(pt) {
+            let state = self.get_state(a);
+            if state == PointState::Empty {
+                has_liberty = true;
+            } else if (state == turn && !suicides.contains(&a)) || (state == opponent && !captives.contains(&a)) {
+                let mut string = GoString::new();
+                self.string_at(a, &mut string);
+                if state == turn && string.num_liberties() > 1 {
+                    has_liberty = true;
+                } else if state == turn {
+                    for &e in &string.points {
+                        suicides.push(e);
+                    }
+                } else if string.num_liberties() == 1 {
+                    has_liberty = true;
+                    for &e in &string.points {
+                        captives.push(e);
+                    }
+                }
+            }
+        }
+        let rule_set = self.get_rule_set();
+        if !has_liberty && (!rule_set.multi_stone_suicide || suicides.is_empty()) {
+            return Err(RuleError::Suicide);
+        }
+
+        // 着手後の石の配置のハッシュ値で過去の局面の繰り返しかチェック
+        let ko_rule = rule_set.ko_rule;
+        if ko_rule != KoRule::Simple {
+            let hash = if has_liberty {
+                captives.iter().fold(self.get_board_hash() ^ point_key(pt, turn), |hash, &e| {
+                    hash ^ point_key(e, opponent)
                 })
+            } else {
+                // 自殺手では置いた石も含めて自分の連が取り上げられます。
+                suicides.iter().fold(self.get_board_hash(), |hash, &e| hash ^ point_key(e, turn))
+            };
+            if self.get_history().is_repetition(ko_rule, hash, self.get_turn().opponent()) {
+                return Err(RuleError::Superko);
+            }
+        }
+        Ok(())
+    }
+
+    /// 局面を変えずに着手movが合法か調べます。check_moveを参照してください。
+    #[inline]
+    fn is_legal(&self, mov: Move) -> bool {
+        self.check_move(mov).is_ok()
+    }
+
+    /// 線形座標pointsの点に黒の置き石を置き、白番にします。
+    ///
+    /// 盤外の点、石のある点、重複した点があればエラーを返し、局面を変えません。
+    /// 置き石の点はhandicapモジュールの関数で求められます。
+    fn set_handicap(&mut self, points: &[LinearCoord]) -> Result<(), RuleError> {
+        for (i, &pt) in points.iter().enumerate() {
+            if !self.is_on_board(pt) {
+                return Err(RuleError::OffBoard);
             }
-            _ => Err("resign is not treated by play"),
+            if self.get_state(pt) != PointState::Empty || points[..i].contains(&pt) {
+                return Err(RuleError::Occupied);
+            }
+        }
+        for &pt in points {
+            self.set_state(pt, PointState::Black);
         }
+        self.set_ko(None);
+        self.set_turn(Color::White);
+        Ok(())
     }
 
     /// 線形座標ptの石によって取れる石を取り上げ、その座標の配列を返します。
@@ -148,15 +472,13 @@ pub trait Rule : Board {
         let opponent = self.get_turn().opponent();
 
         for &a in &self.adjacencies_at(pt) {
-            if self.get_state(a) == opponent.to_pointstate() {
+            if self.get_state(a) == opponent.to_pointstate() && self.num_liberties_at(a) == 0 {
                 let mut string = GoString::new();
                 self.string_at(a, &mut string);
-                if string.num_liberties() == 0 {
-                    self.remove_string(&string);
-                    // TODO - 配列の結合だけどそれ用のメソッドがなく要素1つ1つ追加している。もっと速い方法あり？
-                    for &e in &string.points {
-                        captives.push(e);
-                    }
+                self.remove_string(&string);
+                // TODO - 配列の結合だけどそれ用のメソッドがなく要素1つ1つ追加している。もっと速い方法あり？
+                for &e in &string.points {
+                    captives.push(e);
                 }
             }
         }
@@ -172,16 +494,27 @@ pub trait Rule : Board {
     /// 直前の着手を取り消します。
     fn undo_play(&mut self, move_log: &MoveLog) {
         self.set_ko(move_log.ko);
-        self.switch_turn();
-        match move_log.mov {
-            Move::Linear(i) => {
+        self.set_turn(move_log.turn);
+        self.get_history_mut().truncate(move_log.history_len);
+        self.set_consecutive_passes(move_log.passes);
+        self.set_prisoners(Color::Black, move_log.prisoners.0);
+        self.set_prisoners(Color::White, move_log.prisoners.1);
+        if move_log.mov == Move::Resign {
+            self.set_resigned(None);
+        }
+        if let Move::Linear(i) = move_log.mov {
+            if move_log.suicide {
+                for &pt in &move_log.captives {
+                    self.set_state(pt, move_log.turn.to_pointstate());
+                }
+                self.set_state(i, PointState::Empty);
+            } else {
                 self.set_state(i, PointState::Empty);
                 let opponent = move_log.turn.opponent();
                 for &pt in &move_log.captives {
                     self.set_state(pt, opponent.to_pointstate());
                 }
             }
-            _ => {},
         }
     }
 
@@ -189,9 +522,9 @@ pub trait Rule : Board {
     #[inline]
     fn diagonal_neighbors(&self, pt: LinearCoord) -> [LinearCoord; 4] {
         debug_assert!(self.is_on_board(pt), "pt = {}", pt);
-        let width_plus_2 = self.get_width() + 2;
+        let stride = self.get_width_with_ob();
         //  NE  SE  SW  NW
-        [pt - width_plus_2 + 1, pt + width_plus_2 + 1, pt + width_plus_2 - 1, pt - width_plus_2 - 1]
+        [pt - stride + 1, pt + stride + 1, pt + stride - 1, pt - stride - 1]
     }
 
     /// 眼形か否かを返します。
@@ -231,6 +564,10 @@ pub trait Rule : Board {
 
     /// 終局を仮定して局面のスコアを返します。
     /// 盤上の石の数と眼の空点の数の差がスコアの定義です。
+    /// セキの共有の呼吸点は両方の色に接するので、どちらにも数えません。
+    ///
+    /// 眼の判定だけで数えるプレイアウト用の高速な実装です。
+    /// 駄目やセキ、広い地が残る局面を正しく数えるにはtromp_taylor_scoreを使ってください。
     ///
     /// 終局の条件は、以下の2点です。
     ///
@@ -253,4 +590,102 @@ pub trait Rule : Board {
         }
         s as f32 - self.get_komi()
     }
+
+    /// Tromp-Taylorルールで数えた内訳を返します。
+    ///
+    /// 空点の領域ごとに、片方の色の石だけに接していればその色の地、それ以外は駄目とします。
+    /// 盤上の石はすべて生きているものとします。
+    fn tromp_taylor_score(&self) -> AreaScore {
+        self.area_score(&[])
+    }
+
+    /// 死に石を取り除いて数え上げ(中国ルール)で数えた内訳を返します。
+    ///
+    /// deadには死に石の連の点を与えます。点を含む連全体を死に石として取り除いてから、
+    /// tromp_taylor_scoreと同じように数えます。
+    fn area_score(&self, dead: &[LinearCoord]) -> AreaScore {
+        let dead = string_marks(self, dead);
+        let state = |pt: LinearCoord| if dead.get(pt as usize) == Some(&true) {
+            PointState::Empty
+        } else {
+            self.get_state(pt)
+        };
+        let mut score = AreaScore {
+            komi: self.get_komi(),
+            ..AreaScore::default()
+        };
+        for pt in self.all_points() {
+            match state(pt) {
+                PointState::Black => score.black_stones += 1,
+                PointState::White => score.white_stones += 1,
+                _ => {},
+            }
+        }
+        for region in empty_regions(self, state) {
+            let size = region.points.len() as u32;
+            match region.owner() {
+                PointState::Black => score.black_territory += size,
+                PointState::White => score.white_territory += size,
+                _ => score.dame += size,
+            }
+        }
+        score
+    }
+
+    /// 死に石を取り除いて地とアゲハマ(日本ルール、韓国ルール)で数えた内訳を返します。
+    ///
+    /// deadはarea_scoreと同じです。取り除いた死に石は相手のアゲハマに加えます。
+    /// sekiにはセキの連の点を与えます。セキの連に接する空点の領域は地に数えません。
+    fn territory_score(&self, dead: &[LinearCoord], seki: &[LinearCoord]) -> TerritoryScore {
+        let dead = string_marks(self, dead);
+        let seki = string_marks(self, seki);
+        let is_marked = |marks: &[bool], pt: LinearCoord| marks.get(pt as usize) == Some(&true);
+        let state = |pt: LinearCoord| if is_marked(&dead, pt) {
+            PointState::Empty
+        } else {
+            self.get_state(pt)
+        };
+        let mut score = TerritoryScore {
+            black_prisoners: self.get_prisoners(Color::Black),
+            white_prisoners: self.get_prisoners(Color::White),
+            komi: self.get_komi(),
+            ..TerritoryScore::default()
+        };
+        for pt in self.all_points() {
+            if is_marked(&dead, pt) {
+                match self.get_state(pt) {
+                    PointState::Black => score.white_prisoners += 1,
+                    PointState::White => score.black_prisoners += 1,
+                    _ => {},
+                }
+            }
+        }
+        for region in empty_regions(self, state) {
+            let in_seki = region.points.iter()
+                .any(|&pt| self.adjacencies_at(pt).iter().any(|&a| is_marked(&seki, a)));
+            if in_seki {
+                continue;
+            }
+            let size = region.points.len() as u32;
+            match region.owner() {
+                PointState::Black => score.black_territory += size,
+                PointState::White => score.white_territory += size,
+                _ => {},
+            }
+        }
+        score
+    }
+
+    /// ルールの数え方で、死に石deadを取り除いて数えた黒から見たコミを含む差を返します。
+    fn rule_score(&self, dead: &[LinearCoord]) -> f32 {
+        match self.get_rule_set().scoring {
+            Scoring::Area => self.area_score(dead).margin(),
+            Scoring::Territory => self.territory_score(dead, &self.seki().stones()).margin(),
+        }
+    }
+
+    /// 局面のセキを返します。find_sekiを参照してください。
+    fn seki(&self) -> Seki {
+        find_seki(self)
+    }
 }
@@ -0,0 +1,125 @@
+use history::KoRule;
+
+/// 終局時の数え方です。
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Scoring {
+    /// 石と地を数える数え上げ(中国ルールなど)です。
+    Area,
+    /// 地とアゲハマを数える計算(日本ルールなど)です。
+    Territory,
+}
+
+/// 局面に適用するルールの組です。
+///
+/// コウのルール、複数の石の自殺手の可否、数え方、AGAルールのパス石、既定のコミを選びます。
+/// 主なルールはプリセットとして用意しています。
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct RuleSet {
+    /// コウのルール
+    pub ko_rule: KoRule,
+    /// 2つ以上の石の自殺手を認めるならtrue。1つの石の自殺手はどのルールでも禁止です。
+    pub multi_stone_suicide: bool,
+    /// 数え方
+    pub scoring: Scoring,
+    /// パスするたびに相手にアゲハマを1つ渡し、白が最後にパスするまで終局しない(AGAルール)ならtrue
+    pub pass_stones: bool,
+    /// 既定のコミ
+    pub komi: f32,
+}
+
+impl RuleSet {
+    /// 中国ルールです。
+    pub fn chinese() -> Self {
+        RuleSet {
+            ko_rule: KoRule::Positional,
+            multi_stone_suicide: false,
+            scoring: Scoring::Area,
+            pass_stones: false,
+            komi: 7.5,
+        }
+    }
+
+    /// 日本ルールです。
+    pub fn japanese() -> Self {
+        RuleSet {
+            ko_rule: KoRule::Simple,
+            multi_stone_suicide: false,
+            scoring: Scoring::Territory,
+            pass_stones: false,
+            komi: 6.5,
+        }
+    }
+
+    /// AGA(米国囲碁協会)ルールです。
+    ///
+    /// 地とアゲハマで数えます。パス石と白が最後にパスする終局の条件によって、数え上げと同じ結果になります。
+    pub fn aga() -> Self {
+        RuleSet {
+            ko_rule: KoRule::Situational,
+            multi_stone_suicide: false,
+            scoring: Scoring::Territory,
+            pass_stones: true,
+            komi: 7.5,
+        }
+    }
+
+    /// ニュージーランドルールです。
+    pub fn new_zealand() -> Self {
+        RuleSet {
+            ko_rule: KoRule::Situational,
+            multi_stone_suicide: true,
+            scoring: Scoring::Area,
+            pass_stones: false,
+            komi: 7.0,
+        }
+    }
+
+    /// Tromp-Taylorルールです。
+    pub fn tromp_taylor() -> Self {
+        RuleSet {
+            ko_rule: KoRule::Positional,
+            multi_stone_suicide: true,
+            scoring: Scoring::Area,
+            pass_stones: false,
+            komi: 7.5,
+        }
+    }
+
+    /// 応氏ルールです。独自のコウのルールは同一局面(手番含む)の禁止で近似します。
+    pub fn ing() -> Self {
+        RuleSet {
+            ko_rule: KoRule::Situational,
+            multi_stone_suicide: true,
+            scoring: Scoring::Area,
+            pass_stones: false,
+            komi: 8.0,
+        }
+    }
+
+    /// GTPのkgs-rulesコマンドなどで使われる名前のルールを返します。
+    /// 大文字小文字は区別しません。知らない名前ならNoneを返します。
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "chinese" => Some(RuleSet::chinese()),
+            "japanese" => Some(RuleSet::japanese()),
+            "aga" => Some(RuleSet::aga()),
+            "new_zealand" | "nz" => Some(RuleSet::new_zealand()),
+            "tromp-taylor" | "tromp_taylor" => Some(RuleSet::tromp_taylor()),
+            "ing" => Some(RuleSet::ing()),
+            _ => None,
+        }
+    }
+}
+
+/// 既定のルールです。単純なコウ、自殺手禁止、数え上げ、コミ6.5です。
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            ko_rule: KoRule::Simple,
+            multi_stone_suicide: false,
+            scoring: Scoring::Area,
+            pass_stones: false,
+            komi: 6.5,
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use go_board::*;
+use rule::*;
+use result::GameResult;
+
+/// 空点がつながった領域です。
+#[derive(Clone, Debug)]
+pub struct Region {
+    /// 領域の点の線形座標
+    pub points: Vec<LinearCoord>,
+    /// 黒石に接しているならtrue
+    pub reaches_black: bool,
+    /// 白石に接しているならtrue
+    pub reaches_white: bool,
+}
+
+impl Region {
+    /// 片方の色の石だけに接していればその色を、それ以外はPointState::Emptyを返します。
+    pub fn owner(&self) -> PointState {
+        match (self.reaches_black, self.reaches_white) {
+            (true, false) => PointState::Black,
+            (false, true) => PointState::White,
+            _             => PointState::Empty,
+        }
+    }
+}
+
+/// 盤上の空点の領域をすべて返します。
+///
+/// 各点の状態はstateで与えます。盤上の状態と違う見方(死に石を空点とみなすなど)で領域を求められます。
+pub fn empty_regions<T: Board + ?Sized, F: Fn(LinearCoord) -> PointState>(board: &T, state: F) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut visited = vec![false; board.all_points().end as usize];
+    for pt in board.all_points() {
+        if visited[pt as usize] || state(pt) != PointState::Empty {
+            continue;
+        }
+        let mut region = Region {
+            points: vec![pt],
+            reaches_black: false,
+            reaches_white: false,
+        };
+        visited[pt as usize] = true;
+        let stride = board.get_width_with_ob();
+        let mut index = 0;
+        while index < region.points.len() {
+            let pt = region.points[index];
+            for &a in &[pt - stride, pt + 1, pt + stride, pt - 1] {
+                match state(a) {
+                    PointState::Empty if !visited[a as usize] => {
+                        visited[a as usize] = true;
+                        region.points.push(a);
+                    },
+                    PointState::Black => region.reaches_black = true,
+                    PointState::White => region.reaches_white = true,
+                    _ => {},
+                }
+            }
+            index += 1;
+        }
+        regions.push(region);
+    }
+    regions
+}
+
+/// 数え上げ(中国ルール、Tromp-Taylorルール)の内訳です。
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AreaScore {
+    /// 黒石の数
+    pub black_stones: u32,
+    /// 白石の数
+    pub white_stones: u32,
+    /// 黒の地(黒石だけに接する空点)の数
+    pub black_territory: u32,
+    /// 白の地(白石だけに接する空点)の数
+    pub white_territory: u32,
+    /// 駄目(両方の色に接するか、どちらにも接しない空点)の数
+    pub dame: u32,
+    /// コミ
+    pub komi: f32,
+}
+
+impl AreaScore {
+    /// 黒の石と地の合計を返します。
+    pub fn black_area(&self) -> u32 {
+        self.black_stones + self.black_territory
+    }
+
+    /// 白の石と地の合計を返します。
+    pub fn white_area(&self) -> u32 {
+        self.white_stones + self.white_territory
+    }
+
+    /// コミを含めた黒から見た差を返します。
+    pub fn margin(&self) -> f32 {
+        self.black_area() as f32 - self.white_area() as f32 - self.komi
+    }
+
+    /// 結果を返します。
+    pub fn result(&self) -> GameResult {
+        GameResult::from_score(self.margin())
+    }
+}
+
+/// 地とアゲハマで数える(日本ルール、韓国ルール)内訳です。
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TerritoryScore {
+    /// 黒の地の数
+    pub black_territory: u32,
+    /// 白の地の数
+    pub white_territory: u32,
+    /// 黒が取った白石(死に石を含む)の数
+    pub black_prisoners: u32,
+    /// 白が取った黒石(死に石を含む)の数
+    pub white_prisoners: u32,
+    /// コミ
+    pub komi: f32,
+}
+
+impl TerritoryScore {
+    /// 黒の地とアゲハマの合計を返します。
+    pub fn black_points(&self) -> u32 {
+        self.black_territory + self.black_prisoners
+    }
+
+    /// 白の地とアゲハマの合計を返します。
+    pub fn white_points(&self) -> u32 {
+        self.white_territory + self.white_prisoners
+    }
+
+    /// コミを含めた黒から見た差を返します。
+    pub fn margin(&self) -> f32 {
+        self.black_points() as f32 - self.white_points() as f32 - self.komi
+    }
+
+    /// 結果を返します。
+    pub fn result(&self) -> GameResult {
+        GameResult::from_score(self.margin())
+    }
+}
+
+/// pointsの各点を含む連の石に印をつけた、線形座標で引く配列を返します。石のない点は無視します。
+pub fn string_marks<T: Rule + ?Sized>(position: &T, points: &[LinearCoord]) -> Vec<bool> {
+    let mut marks = vec![false; position.all_points().end as usize];
+    for &pt in points {
+        if !position.is_on_board(pt) || !position.get_state(pt).is_stone() || marks[pt as usize] {
+            continue;
+        }
+        let mut string = GoString::new();
+        position.string_at(pt, &mut string);
+        for &e in &string.points {
+            marks[e as usize] = true;
+        }
+    }
+    marks
+}
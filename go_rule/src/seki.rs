@@ -0,0 +1,128 @@
+use go_board::*;
+use rule::*;
+use score::*;
+
+/// 局面のセキです。
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Seki {
+    /// セキの連。各要素は連の石の線形座標の配列です。
+    pub strings: Vec<Vec<LinearCoord>>,
+    /// セキの連が共有する呼吸点。どちらが打っても自分の連がアタリになります。
+    pub liberties: Vec<LinearCoord>,
+}
+
+impl Seki {
+    /// セキがなければtrueを返します。
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// セキの連の石の線形座標の配列を返します。territory_scoreのsekiに渡せます。
+    pub fn stones(&self) -> Vec<LinearCoord> {
+        self.strings.iter().flat_map(|points| points.iter().cloned()).collect()
+    }
+}
+
+/// 色colorの石を線形座標ptに打つと、石を取らずに自分の連の呼吸点が1つ以下になるならtrueを返します。
+fn is_self_atari<T: Rule + ?Sized>(position: &T, pt: LinearCoord, color: Color) -> bool {
+    let stone = color.to_pointstate();
+    let mut liberties: Vec<LinearCoord> = Vec::new();
+    for &a in &position.adjacencies_at(pt) {
+        let state = position.get_state(a);
+        if state == PointState::Empty {
+            if !liberties.contains(&a) {
+                liberties.push(a);
+            }
+        } else if state.is_stone() {
+            let num_liberties = position.num_liberties_at(a);
+            if state != stone {
+                if num_liberties == 1 {
+                    return false;
+                }
+                continue;
+            }
+            // pt以外に2つ以上の呼吸点がある連につながります。
+            if num_liberties >= 3 {
+                return false;
+            }
+            let mut string = GoString::new();
+            position.string_at(a, &mut string);
+            for &e in &string.liberties {
+                if e != pt && !liberties.contains(&e) {
+                    liberties.push(e);
+                }
+            }
+        }
+        if liberties.len() >= 2 {
+            return false;
+        }
+    }
+    true
+}
+
+/// 線形座標ptが、黒と白の連が共有する呼吸点で、どちらが打っても石を取らずに自分の連がアタリになる点ならtrueを返します。
+///
+/// 周りの連だけを調べる局所的な判定です。セキ以外の攻め合いの呼吸点でもtrueになることがあります。
+/// 局面全体を調べるにはfind_sekiを使ってください。
+pub fn is_seki_liberty<T: Rule + ?Sized>(position: &T, pt: LinearCoord) -> bool {
+    if position.get_state(pt) != PointState::Empty {
+        return false;
+    }
+    let neighbors = position.adjacencies_at(pt);
+    let touches = |state: PointState| neighbors.iter().any(|&a| position.get_state(a) == state);
+    touches(PointState::Black) && touches(PointState::White)
+        && is_self_atari(position, pt, Color::Black) && is_self_atari(position, pt, Color::White)
+}
+
+/// 局面positionのセキを求めます。
+///
+/// is_seki_libertyの点を共有する連のうち、呼吸点がすべてそのような点か自分の眼(自分の色だけに接する空点の領域)である連を、
+/// 黒と白の両方が残る限りセキとします。条件を満たさない連とその呼吸点を除くことを、変化がなくなるまで繰り返します。
+pub fn find_seki<T: Rule + ?Sized>(position: &T) -> Seki {
+    let size = position.all_points().end as usize;
+    let mut owners = vec![PointState::Empty; size];
+    for region in empty_regions(position, |pt| position.get_state(pt)) {
+        let owner = region.owner();
+        for &pt in &region.points {
+            owners[pt as usize] = owner;
+        }
+    }
+
+    let mut liberties: Vec<LinearCoord> = position.all_points()
+        .filter(|&pt| position.is_on_board(pt) && is_seki_liberty(position, pt))
+        .collect();
+    let mut strings: Vec<(PointState, GoString)> = Vec::new();
+    let mut marked = vec![false; size];
+    for &pt in &liberties {
+        for &a in &position.adjacencies_at(pt) {
+            if position.get_state(a).is_stone() && !marked[a as usize] {
+                let mut string = GoString::new();
+                position.string_at(a, &mut string);
+                for &e in &string.points {
+                    marked[e as usize] = true;
+                }
+                strings.push((position.get_state(a), string));
+            }
+        }
+    }
+
+    loop {
+        let num_strings = strings.len();
+        let num_liberties = liberties.len();
+        strings.retain(|&(color, ref string)| string.liberties.iter().all(|&e| {
+            liberties.contains(&e) || owners[e as usize] == color
+        }));
+        liberties.retain(|&pt| [PointState::Black, PointState::White].iter().all(|&color| {
+            strings.iter().any(|&(c, ref string)| c == color && string.liberties.contains(&pt))
+        }));
+        if strings.len() == num_strings && liberties.len() == num_liberties {
+            break;
+        }
+    }
+
+    Seki {
+        strings: strings.into_iter().map(|(_, string)| string.points.to_vec()).collect(),
+        liberties,
+    }
+}
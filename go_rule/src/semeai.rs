@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use go_board::*;
+use rule::*;
+use score::*;
+use eye::*;
+
+/// 攻め合いの結果です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SemeaiOutcome {
+    /// その色が相手の連を取ります。
+    Win(Color),
+    /// どちらも取れずセキになります。
+    Seki,
+}
+
+/// 片方の手番から打ったときの攻め合いの結果と、最善の着手です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SemeaiVerdict {
+    /// 結果
+    pub outcome: SemeaiOutcome,
+    /// 最善の着手。手を抜くのが最善ならMove::Passです。
+    pub key_move: Move,
+}
+
+/// 攻め合いの片方の連の呼吸点の内訳です。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemeaiSide {
+    /// 連の石の1つの線形座標
+    pub head: LinearCoord,
+    /// 外ダメ(相手の連と共有しない、眼の中でもない呼吸点)
+    pub outside: Vec<LinearCoord>,
+    /// 眼の数。2以上なら取られません。
+    pub eyes: u32,
+    /// 眼の中の空点
+    pub eye_points: Vec<LinearCoord>,
+    /// 眼の手数。中手の手数で数えます(1目から6目で1, 2, 3, 5, 8, 12)。
+    pub eye_liberties: u32,
+}
+
+/// 隣接する黒と白の連の攻め合いの分析結果です。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Semeai {
+    /// 黒の連
+    pub black: SemeaiSide,
+    /// 白の連
+    pub white: SemeaiSide,
+    /// 共有する呼吸点(内ダメ)
+    pub shared: Vec<LinearCoord>,
+    /// 黒が先に打ったときの結果
+    pub black_first: SemeaiVerdict,
+    /// 白が先に打ったときの結果
+    pub white_first: SemeaiVerdict,
+}
+
+impl Semeai {
+    /// colorが先に打ったときの結果を返します。
+    #[inline]
+    pub fn verdict(&self, color: Color) -> SemeaiVerdict {
+        match color {
+            Color::Black => self.black_first,
+            Color::White => self.white_first,
+        }
+    }
+}
+
+/// urgent_semeai_moveで調べる連の呼吸点の最大数です。
+pub const MAX_SEMEAI_LIBERTIES: usize = 4;
+
+/// 眼の大きさごとの手数です。
+const EYE_LIBERTIES: [u32; 7] = [0, 1, 2, 3, 5, 8, 12];
+
+/// 線形座標a, bの石を含む、隣接する黒と白の連の攻め合いを分析します。
+///
+/// 外ダメ、内ダメ、眼の手数を数え、それぞれの手番で打ったときの勝ち負けかセキかを数の上で読みます。
+/// 眼は自分の色だけに接する空点の領域で、classify_eye_spaceで二眼になる領域があれば取られないとみなします。
+/// 石がないか、同じ色か、隣接していない連ならNoneを返します。
+pub fn evaluate_semeai<T: Rule + ?Sized>(position: &T, a: LinearCoord, b: LinearCoord) -> Option<Semeai> {
+    if !position.is_on_board(a) || !position.is_on_board(b) {
+        return None;
+    }
+    let (black, white) = match (position.get_state(a), position.get_state(b)) {
+        (PointState::Black, PointState::White) => (a, b),
+        (PointState::White, PointState::Black) => (b, a),
+        _ => return None,
+    };
+    let mut black_string = GoString::new();
+    position.string_at(black, &mut black_string);
+    let mut white_string = GoString::new();
+    position.string_at(white, &mut white_string);
+    let adjacent = black_string.points.iter()
+        .any(|&pt| position.adjacencies_at(pt).iter().any(|a| white_string.points.contains(a)));
+    if !adjacent {
+        return None;
+    }
+
+    let shared: Vec<LinearCoord> = black_string.liberties.iter()
+        .cloned()
+        .filter(|pt| white_string.liberties.contains(pt))
+        .collect();
+    let regions = empty_regions(position, |pt| position.get_state(pt));
+    let black = semeai_side(position, black, &black_string, &shared, &regions, PointState::Black);
+    let white = semeai_side(position, white, &white_string, &shared, &regions, PointState::White);
+
+    let mut race = Race { memo: HashMap::new() };
+    let state = RaceState {
+        outside: [black.outside.len() as u32, white.outside.len() as u32],
+        shared: shared.len() as u32,
+        eye_liberties: [black.eye_liberties, white.eye_liberties],
+    };
+    let verdict = |race: &mut Race, color: Color| {
+        let (outcome, step) = if black.eyes >= 2 && white.eyes >= 2 {
+            (SemeaiOutcome::Seki, Step::Pass)
+        } else if black.eyes >= 2 || white.eyes >= 2 {
+            let winner = if black.eyes >= 2 { Color::Black } else { Color::White };
+            (SemeaiOutcome::Win(winner), if color == winner { Step::FillOutside } else { Step::Pass })
+        } else {
+            race.best(state, color, false)
+        };
+        let opponent = match color {
+            Color::Black => &white,
+            Color::White => &black,
+        };
+        let key_move = match step {
+            Step::FillOutside => opponent.outside.first().or_else(|| shared.first()),
+            Step::FillShared => shared.first(),
+            Step::FillEye => opponent.eye_points.first(),
+            Step::Pass => None,
+        }.map_or(Move::Pass, |&pt| Move::Linear(pt));
+        SemeaiVerdict { outcome, key_move }
+    };
+    let black_first = verdict(&mut race, Color::Black);
+    let white_first = verdict(&mut race, Color::White);
+    Some(Semeai { black, white, shared, black_first, white_first })
+}
+
+/// 手番の側が先に打てば勝ち、相手に先に打たれると勝てない攻め合いの、最善の着手の点を返します。
+///
+/// 呼吸点がMAX_SEMEAI_LIBERTIES以下の隣接する黒と白の連の組を調べます。プレイアウトの着手の候補に使えます。
+pub fn urgent_semeai_move<T: Rule + ?Sized>(position: &T) -> Option<LinearCoord> {
+    let turn = position.get_turn();
+    let mut marked = vec![false; position.all_points().end as usize];
+    for pt in position.all_points() {
+        if marked[pt as usize] || position.get_state(pt) != turn.to_pointstate() {
+            continue;
+        }
+        let mut string = GoString::new();
+        position.string_at(pt, &mut string);
+        for &e in &string.points {
+            marked[e as usize] = true;
+        }
+        if string.num_liberties() > MAX_SEMEAI_LIBERTIES {
+            continue;
+        }
+        for &e in &string.points {
+            for &a in &position.adjacencies_at(e) {
+                if position.get_state(a) != turn.opponent().to_pointstate() {
+                    continue;
+                }
+                let mut opponent = GoString::new();
+                position.string_at(a, &mut opponent);
+                if opponent.num_liberties() > MAX_SEMEAI_LIBERTIES {
+                    continue;
+                }
+                if let Some(semeai) = evaluate_semeai(position, pt, a) {
+                    let first = semeai.verdict(turn);
+                    if first.outcome == SemeaiOutcome::Win(turn) && semeai.verdict(turn.opponent()).outcome != first.outcome {
+                        if let Move::Linear(mov) = first.key_move {
+                            return Some(mov);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// 連stringの呼吸点を内ダメ、眼、外ダメに分けます。
+fn semeai_side<T: Rule + ?Sized>(position: &T, head: LinearCoord, string: &GoString, shared: &[LinearCoord], regions: &[Region], stone: PointState) -> SemeaiSide {
+    let mut side = SemeaiSide {
+        head,
+        outside: Vec::new(),
+        eyes: 0,
+        eye_points: Vec::new(),
+        eye_liberties: 0,
+    };
+    for region in regions.iter().filter(|region| region.owner() == stone) {
+        if !region.points.iter().any(|pt| string.liberties.contains(pt)) {
+            continue;
+        }
+        match classify_eye_space(position, &region.points).eyes() {
+            Eyes::Two => side.eyes += 2,
+            _ => side.eyes += 1,
+        }
+        side.eye_liberties += EYE_LIBERTIES.get(region.points.len()).cloned().unwrap_or(u32::MAX / 4);
+        side.eye_points.extend_from_slice(&region.points);
+    }
+    side.outside = string.liberties.iter()
+        .cloned()
+        .filter(|pt| !shared.contains(pt) && !side.eye_points.contains(pt))
+        .collect();
+    side
+}
+
+/// 数の上の攻め合いの局面です。添字0が黒、1が白です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct RaceState {
+    /// 外ダメの数
+    outside: [u32; 2],
+    /// 内ダメの数
+    shared: u32,
+    /// 眼の手数
+    eye_liberties: [u32; 2],
+}
+
+impl RaceState {
+    /// 添字sideの連の呼吸点の数を返します。
+    fn liberties(&self, side: usize) -> u32 {
+        self.outside[side] + self.shared + self.eye_liberties[side]
+    }
+}
+
+/// 数の上の攻め合いの着手です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Step {
+    /// 相手の外ダメを詰めます。
+    FillOutside,
+    /// 内ダメを詰めます。
+    FillShared,
+    /// 外ダメと内ダメがなくなった相手の眼を詰めます。
+    FillEye,
+    /// 手を抜きます。
+    Pass,
+}
+
+/// 数の上の攻め合いを読む状態です。
+struct Race {
+    /// 局面、手番、直前がパスかどうかごとの黒から見た値(黒の勝ちが1、セキが0、白の勝ちが-1)
+    memo: HashMap<(RaceState, Color, bool), i32>,
+}
+
+impl Race {
+    /// 手番colorの最善の結果と着手を返します。
+    fn best(&mut self, state: RaceState, color: Color, passed: bool) -> (SemeaiOutcome, Step) {
+        let (value, step) = self.search(state, color, passed);
+        let outcome = match value {
+            1 => SemeaiOutcome::Win(Color::Black),
+            -1 => SemeaiOutcome::Win(Color::White),
+            _ => SemeaiOutcome::Seki,
+        };
+        (outcome, step)
+    }
+
+    fn value(&mut self, state: RaceState, color: Color, passed: bool) -> i32 {
+        if let Some(&value) = self.memo.get(&(state, color, passed)) {
+            return value;
+        }
+        let value = self.search(state, color, passed).0;
+        self.memo.insert((state, color, passed), value);
+        value
+    }
+
+    /// 手番colorの黒から見た値と最善の着手を返します。
+    fn search(&mut self, state: RaceState, color: Color, passed: bool) -> (i32, Step) {
+        let (own, opponent, sign) = match color {
+            Color::Black => (0, 1, 1),
+            Color::White => (1, 0, -1),
+        };
+        let mut candidates = Vec::new();
+        if state.outside[opponent] > 0 {
+            let mut next = state;
+            next.outside[opponent] -= 1;
+            candidates.push((next, Step::FillOutside));
+        }
+        if state.shared > 0 {
+            let mut next = state;
+            next.shared -= 1;
+            // 相手を取れない自殺手は打てません。
+            if next.liberties(opponent) == 0 || next.liberties(own) > 0 {
+                candidates.push((next, Step::FillShared));
+            }
+        }
+        if state.outside[opponent] == 0 && state.shared == 0 && state.eye_liberties[opponent] > 0 {
+            let mut next = state;
+            next.eye_liberties[opponent] -= 1;
+            candidates.push((next, Step::FillEye));
+        }
+
+        let mut best = (-2, Step::Pass);
+        for (next, step) in candidates {
+            let value = if next.liberties(opponent) == 0 {
+                1
+            } else {
+                sign * self.value(next, color.opponent(), false)
+            };
+            if value > best.0 {
+                best = (value, step);
+                if value == 1 {
+                    break;
+                }
+            }
+        }
+        let pass = if passed { 0 } else { sign * self.value(state, color.opponent(), true) };
+        if pass > best.0 {
+            best = (pass, Step::Pass);
+        }
+        (sign * best.0, best.1)
+    }
+}
@@ -0,0 +1,259 @@
+use go_board::*;
+use rule::*;
+
+/// 読みの対象にする連の呼吸点の最大数です。
+///
+/// 呼吸点がこの数を超えた連と、MAX_NET_DEPTHより後で呼吸点がこの数になった連は逃げたとみなします。
+pub const MAX_TACTICAL_LIBERTIES: usize = 3;
+
+/// 読みの最大手数です。これを超えた局面は結果不明とします。
+const MAX_TACTICAL_DEPTH: usize = 64;
+
+/// ゲタの候補の手を読む最大手数です。
+///
+/// この手数までは、呼吸点がMAX_TACTICAL_LIBERTIESに戻った連も読み続けます。
+const MAX_NET_DEPTH: usize = 2;
+
+/// 読みの結果です。
+///
+/// 候補の手を絞った読みなので、成功と失敗は証明ではありません。
+/// 取る側は呼吸点を詰める手、アタリの石を助ける手、MAX_NET_DEPTHまでのゲタの候補の手だけを読み、
+/// 呼吸点を詰める準備の手(寄せの手)は読みません。
+/// 逃げる側が手を抜く手は最初の手番でだけ読みます。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TacticalResult {
+    /// 読みの範囲では問いに対して成功しました。
+    LikelySuccess,
+    /// 読みの範囲では問いに対して失敗しました。
+    LikelyFailure,
+    /// ノード数か手数の制限で読み切れませんでした。
+    Unknown,
+}
+
+/// 読みの結果とその要の手です。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tactic {
+    /// 結果
+    pub result: TacticalResult,
+    /// 成功するなら成功する最初の着手です。失敗か結果不明なら、最も有望だった着手かNoneです。
+    /// 逃げる側は手を抜いても活きるならMove::Passです。
+    pub key_move: Option<Move>,
+    /// 打った手の数
+    pub nodes: usize,
+}
+
+/// 読みの途中の値です。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Value {
+    /// 取る側の勝ち
+    Captured,
+    /// 逃げる側の勝ち
+    Escaped,
+    /// 不明
+    Unknown,
+}
+
+/// 取るか逃げるかの読みの状態です。
+struct Searcher {
+    /// 読む連の石の1つの線形座標
+    target: LinearCoord,
+    /// 逃げる側の色
+    defender: Color,
+    /// 打った手の数
+    nodes: usize,
+    /// 打てる手の数の上限
+    max_nodes: usize,
+}
+
+/// 線形座標ptの石を含む連を、取る側が先に打って取れるか読みます。
+///
+/// 呼吸点がMAX_TACTICAL_LIBERTIES以下の連が対象で、それ以外はNoneを返します。
+/// 打った手の数がmax_nodesを超えると結果不明になります。局面はplayとundo_playで元に戻します。
+pub fn can_capture<T: Rule>(position: &mut T, pt: LinearCoord, max_nodes: usize) -> Option<Tactic> {
+    let mut searcher = Searcher::new(position, pt, max_nodes)?;
+    let turn = position.get_turn();
+    let (value, key_move) = searcher.attack(position, 0);
+    position.set_turn(turn);
+    Some(Tactic {
+        result: match value {
+            Value::Captured => TacticalResult::LikelySuccess,
+            Value::Escaped => TacticalResult::LikelyFailure,
+            Value::Unknown => TacticalResult::Unknown,
+        },
+        key_move,
+        nodes: searcher.nodes,
+    })
+}
+
+/// 線形座標ptの石を含む連を、逃げる側が先に打って逃げられるか読みます。can_captureを参照してください。
+pub fn can_escape<T: Rule>(position: &mut T, pt: LinearCoord, max_nodes: usize) -> Option<Tactic> {
+    let mut searcher = Searcher::new(position, pt, max_nodes)?;
+    let turn = position.get_turn();
+    let (value, key_move) = searcher.defend(position, 0);
+    position.set_turn(turn);
+    Some(Tactic {
+        result: match value {
+            Value::Escaped => TacticalResult::LikelySuccess,
+            Value::Captured => TacticalResult::LikelyFailure,
+            Value::Unknown => TacticalResult::Unknown,
+        },
+        key_move,
+        nodes: searcher.nodes,
+    })
+}
+
+impl Searcher {
+    fn new<T: Rule>(position: &T, pt: LinearCoord, max_nodes: usize) -> Option<Self> {
+        if !position.is_on_board(pt) {
+            return None;
+        }
+        let defender = match position.get_state(pt) {
+            PointState::Black => Color::Black,
+            PointState::White => Color::White,
+            _ => return None,
+        };
+        let mut string = GoString::new();
+        position.string_at(pt, &mut string);
+        if string.num_liberties() > MAX_TACTICAL_LIBERTIES {
+            return None;
+        }
+        Some(Searcher { target: pt, defender, nodes: 0, max_nodes })
+    }
+
+    /// 読む連と、それに隣接する相手の連を返します。
+    fn strings<T: Rule>(&self, position: &T) -> (GoString, Vec<GoString>) {
+        let mut string = GoString::new();
+        position.string_at(self.target, &mut string);
+        let attacker = self.defender.opponent().to_pointstate();
+        let mut neighbors: Vec<GoString> = Vec::new();
+        for &e in &string.points {
+            for &a in &position.adjacencies_at(e) {
+                if position.get_state(a) == attacker && !neighbors.iter().any(|n| n.points.contains(&a)) {
+                    let mut neighbor = GoString::new();
+                    position.string_at(a, &mut neighbor);
+                    neighbors.push(neighbor);
+                }
+            }
+        }
+        (string, neighbors)
+    }
+
+    /// colorでmovを打ちます。打てないか、ノード数の上限に達していればNoneを返します。
+    fn play<T: Rule>(&mut self, position: &mut T, color: Color, mov: LinearCoord) -> Option<MoveLog> {
+        if self.nodes >= self.max_nodes {
+            return None;
+        }
+        position.set_turn(color);
+        let move_log = position.play(Move::Linear(mov)).ok()?;
+        self.nodes += 1;
+        Some(move_log)
+    }
+
+    /// 取る側の手番で読みます。値と要の手を返します。
+    fn attack<T: Rule>(&mut self, position: &mut T, depth: usize) -> (Value, Option<Move>) {
+        if depth >= MAX_TACTICAL_DEPTH {
+            return (Value::Unknown, None);
+        }
+        let (string, neighbors) = self.strings(position);
+        let liberties = string.num_liberties();
+        if liberties > MAX_TACTICAL_LIBERTIES || (depth > MAX_NET_DEPTH && liberties == MAX_TACTICAL_LIBERTIES) {
+            return (Value::Escaped, None);
+        }
+        // 呼吸点を詰める手、アタリの自分の石を助ける手、読みの始めならゲタの候補の手
+        let mut moves: Vec<LinearCoord> = string.liberties.to_vec();
+        for neighbor in neighbors.iter().filter(|n| n.num_liberties() == 1) {
+            add_moves(&mut moves, &neighbor.liberties);
+        }
+        if liberties >= 2 && depth <= MAX_NET_DEPTH {
+            for &lib in &string.liberties {
+                let empties: Vec<LinearCoord> = position.adjacencies_at(lib).iter()
+                    .cloned()
+                    .filter(|&a| position.get_state(a) == PointState::Empty)
+                    .collect();
+                add_moves(&mut moves, &empties);
+            }
+        }
+
+        let mut result = (Value::Escaped, None);
+        for mov in moves {
+            let move_log = match self.play(position, self.defender.opponent(), mov) {
+                Some(move_log) => move_log,
+                None if self.nodes >= self.max_nodes => {
+                    result = (Value::Unknown, result.1.or(Some(Move::Linear(mov))));
+                    break;
+                },
+                None => continue,
+            };
+            let value = if position.get_state(self.target) != self.defender.to_pointstate() {
+                Value::Captured
+            } else {
+                self.defend(position, depth + 1).0
+            };
+            position.undo_play(&move_log);
+            match value {
+                Value::Captured => return (Value::Captured, Some(Move::Linear(mov))),
+                Value::Unknown if result.0 == Value::Escaped => result = (Value::Unknown, Some(Move::Linear(mov))),
+                _ => {},
+            }
+        }
+        result
+    }
+
+    /// 逃げる側の手番で読みます。値と要の手を返します。
+    fn defend<T: Rule>(&mut self, position: &mut T, depth: usize) -> (Value, Option<Move>) {
+        if depth >= MAX_TACTICAL_DEPTH {
+            return (Value::Unknown, None);
+        }
+        let (string, neighbors) = self.strings(position);
+        if string.num_liberties() > MAX_TACTICAL_LIBERTIES {
+            return (Value::Escaped, Some(Move::Pass));
+        }
+        // 相手の石を取る手、呼吸点を伸ばす手、相手の石をアタリにする手の順に読みます。
+        let mut moves: Vec<LinearCoord> = Vec::new();
+        for neighbor in neighbors.iter().filter(|n| n.num_liberties() == 1) {
+            add_moves(&mut moves, &neighbor.liberties);
+        }
+        add_moves(&mut moves, &string.liberties);
+        for neighbor in neighbors.iter().filter(|n| n.num_liberties() == 2) {
+            add_moves(&mut moves, &neighbor.liberties);
+        }
+
+        let mut result = (Value::Captured, None);
+        for mov in moves {
+            let move_log = match self.play(position, self.defender, mov) {
+                Some(move_log) => move_log,
+                None if self.nodes >= self.max_nodes => {
+                    result = (Value::Unknown, result.1.or(Some(Move::Linear(mov))));
+                    break;
+                },
+                None => continue,
+            };
+            let value = self.attack(position, depth + 1).0;
+            position.undo_play(&move_log);
+            match value {
+                Value::Escaped => return (Value::Escaped, Some(Move::Linear(mov))),
+                Value::Unknown if result.0 == Value::Captured => result = (Value::Unknown, Some(Move::Linear(mov))),
+                _ => {},
+            }
+        }
+        // 最初の手番で呼吸点が2つ以上あれば、手を抜いても取られないか読みます。
+        // 途中の局面で手を抜く手は読みが広がりすぎるので読みません。
+        if result.0 != Value::Escaped && depth == 0 && string.num_liberties() >= 2 {
+            match self.attack(position, depth + 1).0 {
+                Value::Escaped => return (Value::Escaped, Some(Move::Pass)),
+                Value::Unknown if result.0 == Value::Captured => result = (Value::Unknown, Some(Move::Pass)),
+                _ => {},
+            }
+        }
+        result
+    }
+}
+
+/// pointsのうちmovesにない点をmovesに加えます。
+fn add_moves(moves: &mut Vec<LinearCoord>, points: &[LinearCoord]) {
+    for &pt in points {
+        if !moves.contains(&pt) {
+            moves.push(pt);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! 局面のZobristハッシュのためのキーです。
+//!
+//! キーは(線形座標, 石の色)ごと、手番、コウによる着手禁止点ごとに用意します。
+//! 空点、盤外、黒番、コウなしのキーは0なので、空の盤面で黒番のハッシュ値は0です。
+
+use go_board::*;
+
+/// キーを用意する配列サイズです。GTPの最大サイズである25路盤まで扱えます。
+const MAX_ARRAY_SIZE: usize = array_size!(25, 1);
+
+/// splitmix64で乱数列のi番目の値を返します。
+const fn splitmix64(i: u64) -> u64 {
+    let mut z = i.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+const fn make_keys(offset: usize) -> [u64; MAX_ARRAY_SIZE] {
+    let mut keys = [0; MAX_ARRAY_SIZE];
+    let mut i = 0;
+    while i < MAX_ARRAY_SIZE {
+        keys[i] = splitmix64((offset + i) as u64);
+        i += 1;
+    }
+    keys
+}
+
+static BLACK_KEYS: [u64; MAX_ARRAY_SIZE] = make_keys(0);
+static WHITE_KEYS: [u64; MAX_ARRAY_SIZE] = make_keys(MAX_ARRAY_SIZE);
+static KO_KEYS: [u64; MAX_ARRAY_SIZE] = make_keys(MAX_ARRAY_SIZE * 2);
+const WHITE_TURN_KEY: u64 = splitmix64((MAX_ARRAY_SIZE * 3) as u64);
+
+/// 線形座標ptの状態stateのキーを返します。
+#[inline]
+pub fn point_key(pt: LinearCoord, state: PointState) -> u64 {
+    match state {
+        PointState::Black => BLACK_KEYS[pt as usize],
+        PointState::White => WHITE_KEYS[pt as usize],
+        _                 => 0,
+    }
+}
+
+/// 手番turnのキーを返します。
+#[inline]
+pub fn turn_key(turn: Color) -> u64 {
+    match turn {
+        Color::Black => 0,
+        Color::White => WHITE_TURN_KEY,
+    }
+}
+
+/// コウによる着手禁止点koのキーを返します。
+#[inline]
+pub fn ko_key(ko: Option<LinearCoord>) -> u64 {
+    match ko {
+        Some(pt) => KO_KEYS[pt as usize],
+        None     => 0,
+    }
+}
@@ -0,0 +1,47 @@
+use std::error::Error;
+use std::fmt;
+use go_rule::error::RuleError;
+
+/// SGFの読み込みや再生で起こるエラーの種類です。
+#[derive(Debug, PartialEq)]
+pub enum SgfError {
+    /// 構文エラーです。エラーが見つかった位置(バイト単位)を持ちます。
+    Syntax(usize),
+    /// 座標として解釈できない値です。
+    InvalidPoint(String),
+    /// プロパティの値として解釈できない値です。
+    InvalidValue {
+        ident: String,
+        value: String,
+    },
+    /// SZプロパティの盤サイズと局面のサイズが一致しません。
+    SizeMismatch,
+    /// 本譜の着手が非合法です。何手目か、着手の値、非合法の理由を持ちます。
+    IllegalMove {
+        move_number: usize,
+        value: String,
+        reason: RuleError,
+    },
+}
+
+impl fmt::Display for SgfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SgfError::Syntax(pos) => write!(f, "syntax error at {}", pos),
+            SgfError::InvalidPoint(ref value) => write!(f, "invalid point [{}]", value),
+            SgfError::InvalidValue { ref ident, ref value } => write!(f, "invalid value {}[{}]", ident, value),
+            SgfError::SizeMismatch => write!(f, "board size mismatch"),
+            SgfError::IllegalMove { move_number, ref value, ref reason } =>
+                write!(f, "illegal move {} [{}]: {}", move_number, value, reason),
+        }
+    }
+}
+
+impl Error for SgfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SgfError::IllegalMove { ref reason, .. } => Some(reason),
+            _ => None,
+        }
+    }
+}
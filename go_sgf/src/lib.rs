@@ -0,0 +1,355 @@
+//! SGF(Smart Game Format) FF[4]の読み書きを提供します。
+//!
+//! 構文はFF[4]の定義どおりに解析し、プロパティの値はエスケープされたまま保持します。
+//! 書き出しは保持している値をそのまま出力するので、読み込んだファイルを同じ内容で保存できます。
+//!
+//! SGFの座標("aa"形式)とgo_boardの線形座標の変換と、go_ruleの局面への本譜の再生も提供します。
+//!
+//! # 使い方
+//! ```
+//! extern crate go_rule;
+//! extern crate go_sgf;
+//!
+//! use go_rule::position::Position9;
+//!
+//! let collection = go_sgf::parse("(;GM[1]FF[4]SZ[9]KM[7];B[ee];W[cc])").unwrap();
+//! let mut position = Position9::new();
+//! let move_logs = go_sgf::replay(&collection[0], &mut position).unwrap();
+//! assert_eq!(move_logs.len(), 2);
+//! assert_eq!(format!("{}", go_sgf::record(&position, &move_logs)),
+//!            "(;FF[4]GM[1]CA[UTF-8]AP[go_sgf:0.1.0]SZ[9]KM[7];B[ee];W[cc])");
+//! ```
+
+extern crate go_board;
+extern crate go_rule;
+
+use std::fmt;
+use go_board::LinearCoord;
+use go_rule::result::GameResult;
+
+mod error;
+pub use error::*;
+mod parser;
+pub use parser::*;
+pub mod value;
+mod point;
+pub use point::*;
+mod replay;
+pub use replay::*;
+
+/// プロパティです。値はエスケープされたまま保持します。
+#[derive(Clone, Debug, PartialEq)]
+pub struct Property {
+    pub ident: String,
+    pub values: Vec<String>,
+}
+
+/// ノードです。プロパティをファイル中の順に保持します。
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Node {
+    pub properties: Vec<Property>,
+}
+
+impl Node {
+    pub fn new() -> Self {
+        Node {
+            properties: Vec::new(),
+        }
+    }
+
+    /// プロパティを返します。
+    pub fn get(&self, ident: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.ident == ident)
+    }
+
+    /// プロパティの最初の値をエスケープされたまま返します。
+    pub fn get_value(&self, ident: &str) -> Option<&str> {
+        self.get(ident).and_then(|p| p.values.first()).map(|v| v.as_str())
+    }
+
+    /// プロパティの最初の値をText型として返します。
+    pub fn get_text(&self, ident: &str) -> Option<String> {
+        self.get_value(ident).map(value::unescape)
+    }
+
+    /// プロパティに値を1つ設定します。valueはエスケープ済みの値です。
+    pub fn set(&mut self, ident: &str, value: &str) {
+        let values = vec![value.to_string()];
+        match self.properties.iter_mut().find(|p| p.ident == ident) {
+            Some(property) => property.values = values,
+            None => self.properties.push(Property { ident: ident.to_string(), values }),
+        }
+    }
+
+    /// プロパティにText型の値を設定します。
+    pub fn set_text(&mut self, ident: &str, text: &str) {
+        self.set(ident, &value::escape(text));
+    }
+
+    /// プロパティを取り除きます。
+    pub fn remove(&mut self, ident: &str) {
+        self.properties.retain(|p| p.ident != ident);
+    }
+}
+
+/// ゲームツリーです。ノードの列と、その後に続く変化(分岐)を保持します。
+/// 変化の最初のものが本譜です。
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct GameTree {
+    pub nodes: Vec<Node>,
+    pub variations: Vec<GameTree>,
+}
+
+impl GameTree {
+    pub fn new() -> Self {
+        GameTree {
+            nodes: Vec::new(),
+            variations: Vec::new(),
+        }
+    }
+
+    /// ルートノードを返します。
+    pub fn root(&self) -> Option<&Node> {
+        self.nodes.first()
+    }
+
+    /// 本譜のノードを順に返します。
+    pub fn main_line(&self) -> Vec<&Node> {
+        let mut nodes = Vec::new();
+        let mut tree = self;
+        loop {
+            nodes.extend(tree.nodes.iter());
+            match tree.variations.first() {
+                Some(variation) => tree = variation,
+                None => return nodes,
+            }
+        }
+    }
+
+    /// ルートノードのプロパティを型Tとして解釈します。
+    fn parse_root<T: std::str::FromStr>(&self, ident: &str) -> Result<Option<T>, SgfError> {
+        match self.root().and_then(|node| node.get_value(ident)) {
+            Some(value) => value.trim().parse().map(Some).map_err(|_| SgfError::InvalidValue {
+                ident: ident.to_string(),
+                value: value.to_string(),
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// SZプロパティの盤の幅と高さを返します。SZがなければ19路盤です。
+    pub fn board_size(&self) -> Result<(LinearCoord, LinearCoord), SgfError> {
+        let value = match self.root().and_then(|node| node.get_value("SZ")) {
+            Some(value) => value,
+            None => return Ok((19, 19)),
+        };
+        let invalid = || SgfError::InvalidValue { ident: "SZ".to_string(), value: value.to_string() };
+        let (width, height) = match value::split_compose(value) {
+            Some((width, height)) => (width, height),
+            None => (value, value),
+        };
+        let width = width.trim().parse::<LinearCoord>().map_err(|_| invalid())?;
+        let height = height.trim().parse::<LinearCoord>().map_err(|_| invalid())?;
+        if !(1..=52).contains(&width) || !(1..=52).contains(&height) {
+            return Err(invalid());
+        }
+        Ok((width, height))
+    }
+
+    /// KMプロパティのコミを返します。
+    pub fn komi(&self) -> Result<Option<f32>, SgfError> {
+        self.parse_root("KM")
+    }
+
+    /// HAプロパティの置き石の数を返します。
+    pub fn handicap(&self) -> Result<Option<u32>, SgfError> {
+        self.parse_root("HA")
+    }
+
+    /// REプロパティの対局結果を返します。
+    pub fn result(&self) -> Option<String> {
+        self.root().and_then(|node| node.get_text("RE"))
+    }
+
+    /// REプロパティの対局結果をGameResultとして返します。
+    pub fn game_result(&self) -> Result<Option<GameResult>, SgfError> {
+        match self.result() {
+            Some(value) => GameResult::parse(&value)
+                .map(Some)
+                .ok_or(SgfError::InvalidValue { ident: "RE".to_string(), value }),
+            None => Ok(None),
+        }
+    }
+
+    /// RUプロパティのルール名を返します。
+    pub fn rules(&self) -> Option<String> {
+        self.root().and_then(|node| node.get_text("RU"))
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.ident)?;
+        for value in &self.values {
+            write!(f, "[{}]", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, ";")?;
+        for property in &self.properties {
+            property.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GameTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for node in &self.nodes {
+            node.fmt(f)?;
+        }
+        for variation in &self.variations {
+            variation.fmt(f)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use go_board::*;
+    use go_rule::position::*;
+    use go_rule::rule::*;
+    use go_rule::error::*;
+    use go_rule::result::*;
+    use ::*;
+
+    #[test]
+    fn test_parse_variations() {
+        let s = "(;FF[4]GM[1]SZ[19]\n;B[pd];W[dp]\n(;B[pq];W[dd])\n(;B[dd]C[variation]))";
+        let collection = parse(s).unwrap();
+        assert_eq!(collection.len(), 1);
+        let tree = &collection[0];
+        assert_eq!(tree.nodes.len(), 3);
+        assert_eq!(tree.variations.len(), 2);
+        assert_eq!(tree.variations[1].nodes[0].get_text("C"), Some("variation".to_string()));
+        let main_line = tree.main_line();
+        assert_eq!(main_line.len(), 5);
+        assert_eq!(main_line[4].get_value("W"), Some("dd"));
+        assert_eq!(format!("{}", tree), s.replace("\n", ""));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(""), Err(SgfError::Syntax(0)));
+        assert_eq!(parse("(;B[aa]"), Err(SgfError::Syntax(7)));
+        assert_eq!(parse("(;B[aa)"), Err(SgfError::Syntax(7)));
+        assert_eq!(parse("()"), Err(SgfError::Syntax(1)));
+        assert_eq!(parse("(;B)"), Err(SgfError::Syntax(3)));
+        assert!(parse("(;FF[4]) (;FF[4])").unwrap().len() == 2);
+        // FF[3]の小文字入りのプロパティ名
+        assert_eq!(parse("(;AddBlack[aa])").unwrap()[0].nodes[0].get_value("AB"), Some("aa"));
+    }
+
+    #[test]
+    fn test_escape() {
+        let tree = &parse("(;C[a\\]b\\\\c\\\nd\te\nf]GN[x\\:y])").unwrap()[0];
+        assert_eq!(tree.nodes[0].get_value("C"), Some("a\\]b\\\\c\\\nd\te\nf"));
+        assert_eq!(tree.nodes[0].get_text("C"), Some("a]b\\cd e\nf".to_string()));
+        assert_eq!(value::split_compose("x\\:y"), None);
+        assert_eq!(value::split_compose("aa:cc"), Some(("aa", "cc")));
+        let mut node = Node::new();
+        node.set_text("C", "[1:2]\\");
+        assert_eq!(format!("{}", node), ";C[[1\\:2\\]\\\\]");
+        assert_eq!(parse(&format!("({})", node)).unwrap()[0].nodes[0].get_text("C"), Some("[1:2]\\".to_string()));
+    }
+
+    #[test]
+    fn test_points() {
+        let pos = Position19::new();
+        assert_eq!(sgf_to_move(&pos, "aa").unwrap(), Move::Linear(pos.xy_to_linear(1, 1)));
+        assert_eq!(sgf_to_move(&pos, "sb").unwrap(), Move::Linear(pos.xy_to_linear(19, 2)));
+        assert_eq!(sgf_to_move(&pos, "").unwrap(), Move::Pass);
+        assert_eq!(sgf_to_move(&pos, "tt").unwrap(), Move::Pass);
+        assert!(sgf_to_move(&pos, "ta").is_err());
+        assert!(sgf_to_move(&Position21::new(), "tt").is_ok());
+        assert_eq!(point_to_sgf(&pos, pos.xy_to_linear(4, 16)), "dp");
+        let values = vec!["aa:bc".to_string(), "ss".to_string()];
+        assert_eq!(sgf_to_points(&pos, &values).unwrap().len(), 7);
+        assert!(sgf_to_points(&pos, &["bc:aa".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_game_info() {
+        let tree = &parse("(;SZ[9:13]KM[6.5]HA[2]RE[W+R]RU[Japanese])").unwrap()[0];
+        assert_eq!(tree.board_size(), Ok((9, 13)));
+        assert_eq!(tree.komi(), Ok(Some(6.5)));
+        assert_eq!(tree.handicap(), Ok(Some(2)));
+        assert_eq!(tree.result(), Some("W+R".to_string()));
+        assert_eq!(tree.game_result(), Ok(Some(GameResult::Win(Color::White, WinReason::Resign))));
+        assert_eq!(tree.rules(), Some("Japanese".to_string()));
+        assert_eq!(parse("(;FF[4])").unwrap()[0].board_size(), Ok((19, 19)));
+        assert!(parse("(;KM[x])").unwrap()[0].komi().is_err());
+        assert!(parse("(;RE[x])").unwrap()[0].game_result().is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let tree = &parse("(;SZ[9]KM[0.5]AB[aa:ab][ca]AW[ba]PL[W];W[];B[bb];W[cc])").unwrap()[0];
+        let mut pos = Position9::new();
+        let move_logs = replay(tree, &mut pos).unwrap();
+        assert_eq!(move_logs.len(), 3);
+        assert_eq!(pos.get_komi(), 0.5);
+        // 黒のbbで白のbaが取られています。
+        assert_eq!(pos.get_state(pos.xy_to_linear(2, 1)), PointState::Empty);
+        assert_eq!(pos.get_state(pos.xy_to_linear(1, 2)), PointState::Black);
+        assert_eq!(pos.get_turn(), Color::Black);
+        assert_eq!(format!("{}", record(&pos, &move_logs)),
+                   "(;FF[4]GM[1]CA[UTF-8]AP[go_sgf:0.1.0]SZ[9]KM[0.5]AB[aa][ca][ab]AW[ba]PL[W];W[];B[bb];W[cc])");
+        // 書き出した棋譜を再生すると同じ局面になります。
+        let mut replayed = Position9::new();
+        replay(&parse(&record(&pos, &move_logs).to_string()).unwrap()[0], &mut replayed).unwrap();
+        assert_eq!(replayed.get_hash(), pos.get_hash());
+
+        let mut move_logs = move_logs;
+        move_logs.push(pos.play(Move::Resign).unwrap());
+        assert_eq!(format!("{}", record(&pos, &move_logs)),
+                   "(;FF[4]GM[1]CA[UTF-8]AP[go_sgf:0.1.0]SZ[9]KM[0.5]RE[W+R]AB[aa][ca][ab]AW[ba]PL[W];W[];B[bb];W[cc])");
+
+        // 置き石の局面はHAも書き出します。
+        let tree = &parse("(;SZ[9]HA[2]AB[cc][gg]PL[W];W[ee])").unwrap()[0];
+        let mut pos = Position9::new();
+        let move_logs = replay(tree, &mut pos).unwrap();
+        let recorded = record(&pos, &move_logs);
+        assert_eq!(recorded.handicap(), Ok(Some(2)));
+        let mut replayed = Position9::new();
+        replay(&parse(&recorded.to_string()).unwrap()[0], &mut replayed).unwrap();
+        assert_eq!(replayed.get_hash(), pos.get_hash());
+    }
+
+    #[test]
+    fn test_replay_errors() {
+        let mut pos = Position19::new();
+        assert_eq!(replay(&parse("(;SZ[9])").unwrap()[0], &mut pos).err(), Some(SgfError::SizeMismatch));
+        let tree = &parse("(;SZ[9];B[ab];W[bb];B[ba];W[aa])").unwrap()[0];
+        let mut pos = Position9::new();
+        assert_eq!(replay(tree, &mut pos).err(), Some(SgfError::IllegalMove {
+            move_number: 4,
+            value: "aa".to_string(),
+            reason: RuleError::Suicide,
+        }));
+        let tree = &parse("(;SZ[9];B[ee];W[ee])").unwrap()[0];
+        assert_eq!(replay(tree, &mut Position9::new()).err(), Some(SgfError::IllegalMove {
+            move_number: 2,
+            value: "ee".to_string(),
+            reason: RuleError::Occupied,
+        }));
+        let tree = &parse("(;SZ[9];B[zz])").unwrap()[0];
+        assert_eq!(replay(tree, &mut Position9::new()).err(), Some(SgfError::InvalidPoint("zz".to_string())));
+    }
+}
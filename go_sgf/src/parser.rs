@@ -0,0 +1,133 @@
+use ::{Property, Node, GameTree, SgfError};
+
+/// SGFの文字列を解析してGameTreeの配列(Collection)を返します。
+///
+/// FF[3]との互換のため、プロパティ名の小文字は読み飛ばします。
+pub fn parse(s: &str) -> Result<Vec<GameTree>, SgfError> {
+    let mut parser = Parser {
+        s,
+        bytes: s.as_bytes(),
+        pos: 0,
+    };
+    let mut collection = Vec::new();
+    parser.skip_whitespace();
+    while parser.pos < parser.bytes.len() {
+        collection.push(parser.game_tree()?);
+        parser.skip_whitespace();
+    }
+    if collection.is_empty() {
+        return Err(SgfError::Syntax(0));
+    }
+    Ok(collection)
+}
+
+/// 再帰下降構文解析器です。区切り文字がすべてASCIIなのでバイト単位で走査します。
+struct Parser<'a> {
+    s: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            if !b.is_ascii_whitespace() {
+                break;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), SgfError> {
+        self.skip_whitespace();
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(SgfError::Syntax(self.pos))
+        }
+    }
+
+    /// GameTree = "(" Sequence { GameTree } ")"
+    fn game_tree(&mut self) -> Result<GameTree, SgfError> {
+        self.expect(b'(')?;
+        let mut tree = GameTree::new();
+        self.skip_whitespace();
+        while self.peek() == Some(b';') {
+            tree.nodes.push(self.node()?);
+            self.skip_whitespace();
+        }
+        if tree.nodes.is_empty() {
+            return Err(SgfError::Syntax(self.pos));
+        }
+        while self.peek() == Some(b'(') {
+            tree.variations.push(self.game_tree()?);
+            self.skip_whitespace();
+        }
+        self.expect(b')')?;
+        Ok(tree)
+    }
+
+    /// Node = ";" { Property }
+    fn node(&mut self) -> Result<Node, SgfError> {
+        self.expect(b';')?;
+        let mut node = Node::new();
+        self.skip_whitespace();
+        while let Some(b) = self.peek() {
+            if !b.is_ascii_alphabetic() {
+                break;
+            }
+            node.properties.push(self.property()?);
+            self.skip_whitespace();
+        }
+        Ok(node)
+    }
+
+    /// Property = PropIdent PropValue { PropValue }
+    fn property(&mut self) -> Result<Property, SgfError> {
+        let mut ident = String::new();
+        while let Some(b) = self.peek() {
+            if b.is_ascii_uppercase() {
+                ident.push(b as char);
+            } else if !b.is_ascii_lowercase() {
+                break;
+            }
+            self.pos += 1;
+        }
+        if ident.is_empty() {
+            return Err(SgfError::Syntax(self.pos));
+        }
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        while self.peek() == Some(b'[') {
+            values.push(self.value()?);
+            self.skip_whitespace();
+        }
+        if values.is_empty() {
+            return Err(SgfError::Syntax(self.pos));
+        }
+        Ok(Property { ident, values })
+    }
+
+    /// PropValue = "[" CValueType "]"
+    /// 値はエスケープされたまま返します。
+    fn value(&mut self) -> Result<String, SgfError> {
+        self.expect(b'[')?;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(b']') => break,
+                Some(b'\\') => self.pos += 2,
+                Some(_) => self.pos += 1,
+                None => return Err(SgfError::Syntax(self.bytes.len())),
+            }
+        }
+        let value = self.s[start..self.pos].to_string();
+        self.pos += 1;
+        Ok(value)
+    }
+}
@@ -0,0 +1,88 @@
+//! SGFの座標("aa"形式)と線形座標の変換です。
+//!
+//! SGFの座標はxy座標と同じく左上が原点で、1から26は"a"から"z"、27から52は"A"から"Z"で表します。
+
+use go_board::*;
+use SgfError;
+use value::split_compose;
+
+/// SGFの座標の1文字を1から始まる座標値に変換します。
+fn coord_from_char(c: u8) -> Option<u8> {
+    match c {
+        b'a'..=b'z' => Some(c - b'a' + 1),
+        b'A'..=b'Z' => Some(c - b'A' + 27),
+        _           => None,
+    }
+}
+
+/// 1から始まる座標値をSGFの座標の1文字に変換します。
+fn coord_to_char(x: u8) -> char {
+    if x <= 26 {
+        (b'a' + x - 1) as char
+    } else {
+        (b'A' + x - 27) as char
+    }
+}
+
+/// SGFの座標を線形座標に変換します。盤外の座標はエラーです。
+pub fn sgf_to_point<T: Board>(board: &T, value: &str) -> Result<LinearCoord, SgfError> {
+    let bytes = value.as_bytes();
+    if bytes.len() == 2 {
+        if let (Some(x), Some(y)) = (coord_from_char(bytes[0]), coord_from_char(bytes[1])) {
+            if x as LinearCoord <= board.get_width() && y as LinearCoord <= board.get_height() {
+                return Ok(board.xy_to_linear(x, y));
+            }
+        }
+    }
+    Err(SgfError::InvalidPoint(value.to_string()))
+}
+
+/// SGFのB, Wプロパティの値をMoveに変換します。
+/// 空文字列と、19路以下の盤での"tt"はパスです。
+pub fn sgf_to_move<T: Board>(board: &T, value: &str) -> Result<Move, SgfError> {
+    if value.is_empty() || (value == "tt" && board.get_width() <= 19 && board.get_height() <= 19) {
+        Ok(Move::Pass)
+    } else {
+        sgf_to_point(board, value).map(Move::Linear)
+    }
+}
+
+/// 線形座標をSGFの座標に変換します。
+pub fn point_to_sgf<T: Board>(board: &T, pt: LinearCoord) -> String {
+    let (x, y) = board.linear_to_xy(pt);
+    format!("{}{}", coord_to_char(x), coord_to_char(y))
+}
+
+/// MoveをSGFのB, Wプロパティの値に変換します。パスは空文字列です。
+/// 投了はSGFでは着手として表せないのでNoneを返します。
+pub fn move_to_sgf<T: Board>(board: &T, mov: Move) -> Option<String> {
+    match mov {
+        Move::Pass      => Some(String::new()),
+        Move::Resign    => None,
+        Move::Linear(pt) => Some(point_to_sgf(board, pt)),
+    }
+}
+
+/// AB, AW, AEなどの点のリストの値を線形座標の配列に変換します。
+/// "aa:cc"のような長方形の圧縮表現も展開します。
+pub fn sgf_to_points<T: Board>(board: &T, values: &[String]) -> Result<Vec<LinearCoord>, SgfError> {
+    let mut points = Vec::new();
+    for value in values {
+        match split_compose(value) {
+            Some((from, to)) => {
+                let (x1, y1) = board.linear_to_xy(sgf_to_point(board, from)?);
+                let (x2, y2) = board.linear_to_xy(sgf_to_point(board, to)?);
+                if x1 > x2 || y1 > y2 {
+                    return Err(SgfError::InvalidPoint(value.clone()));
+                }
+                for y in y1..y2 + 1 {
+                    for x in x1..x2 + 1 {
+                        points.push(board.xy_to_linear(x, y));
+                    }
+                }
+            },
+            None => points.push(sgf_to_point(board, value)?),
+        }
+    }
+    Ok(points)
+}
@@ -0,0 +1,115 @@
+use go_board::*;
+use go_rule::rule::*;
+use ::{GameTree, Node, Property, SgfError};
+use point::*;
+
+/// ノードのセットアッププロパティ(AB, AW, AE, PL)を局面に反映します。
+fn apply_setup<T: Rule>(node: &Node, position: &mut T) -> Result<(), SgfError> {
+    for &(ident, state) in &[("AE", PointState::Empty), ("AB", PointState::Black), ("AW", PointState::White)] {
+        if let Some(property) = node.get(ident) {
+            for pt in sgf_to_points(position, &property.values)? {
+                position.set_state(pt, state);
+            }
+        }
+    }
+    if let Some(value) = node.get_value("PL") {
+        match value {
+            "B" | "b" => position.set_turn(Color::Black),
+            "W" | "w" => position.set_turn(Color::White),
+            _ => return Err(SgfError::InvalidValue { ident: "PL".to_string(), value: value.to_string() }),
+        }
+    }
+    Ok(())
+}
+
+/// GameTreeの本譜(各分岐の最初の変化)を局面positionに再生します。
+///
+/// ルートノードのSZが局面のサイズと一致しなければエラーです。KMがあればコミを設定します。
+/// 非合法な着手があれば、それが何手目かを含むSgfError::IllegalMoveを返します。
+/// 成功すると本譜の着手のMoveLogの配列を返します。
+pub fn replay<T: Rule>(tree: &GameTree, position: &mut T) -> Result<Vec<MoveLog>, SgfError> {
+    if tree.board_size()? != (position.get_width(), position.get_height()) {
+        return Err(SgfError::SizeMismatch);
+    }
+    if let Some(komi) = tree.komi()? {
+        position.set_komi(komi);
+    }
+    let mut move_logs = Vec::new();
+    for node in tree.main_line() {
+        apply_setup(node, position)?;
+        for &(ident, color) in &[("B", Color::Black), ("W", Color::White)] {
+            if let Some(value) = node.get_value(ident) {
+                let mov = sgf_to_move(position, value)?;
+                position.set_turn(color);
+                let move_log = position.play(mov).map_err(|reason| SgfError::IllegalMove {
+                    move_number: move_logs.len() + 1,
+                    value: value.to_string(),
+                    reason,
+                })?;
+                move_logs.push(move_log);
+            }
+        }
+    }
+    Ok(move_logs)
+}
+
+/// 局面positionのサイズとコミで、move_logsの着手を本譜とするGameTreeを返します。
+/// 局面が終局していれば結果をREに書き出します。
+///
+/// move_logsを取り消した開始局面の石をAB, AWに、手番が白ならPLに書き出します。
+/// 開始局面が2つ以上の黒石だけで白番なら、置き石の数をHAに書き出します。
+pub fn record<T: Rule + Clone>(position: &T, move_logs: &[MoveLog]) -> GameTree {
+    let mut start = position.clone();
+    for move_log in move_logs.iter().rev() {
+        start.undo_play(move_log);
+    }
+
+    let mut root = Node::new();
+    root.set("FF", "4");
+    root.set("GM", "1");
+    root.set("CA", "UTF-8");
+    root.set("AP", &format!("go_sgf:{}", env!("CARGO_PKG_VERSION")));
+    let (width, height) = (position.get_width(), position.get_height());
+    if width == height {
+        root.set("SZ", &width.to_string());
+    } else {
+        root.set("SZ", &format!("{}:{}", width, height));
+    }
+    root.set("KM", &position.get_komi().to_string());
+    if let Some(result) = position.get_result() {
+        root.set("RE", &result.to_string());
+    }
+    let stones = |state: PointState| -> Vec<String> {
+        start.all_points()
+            .filter(|&pt| start.get_state(pt) == state)
+            .map(|pt| point_to_sgf(&start, pt))
+            .collect()
+    };
+    let (black, white) = (stones(PointState::Black), stones(PointState::White));
+    if black.len() >= 2 && white.is_empty() && start.get_turn() == Color::White {
+        root.set("HA", &black.len().to_string());
+    }
+    for &(ident, values) in &[("AB", &black), ("AW", &white)] {
+        if !values.is_empty() {
+            root.properties.push(Property { ident: ident.to_string(), values: values.clone() });
+        }
+    }
+    if start.get_turn() == Color::White {
+        root.set("PL", "W");
+    }
+
+    let mut tree = GameTree::new();
+    tree.nodes.push(root);
+    for move_log in move_logs {
+        if let Some(value) = move_to_sgf(position, move_log.get_move()) {
+            let ident = match move_log.get_turn() {
+                Color::Black => "B",
+                Color::White => "W",
+            };
+            let mut node = Node::new();
+            node.properties.push(Property { ident: ident.to_string(), values: vec![value] });
+            tree.nodes.push(node);
+        }
+    }
+    tree
+}
@@ -0,0 +1,61 @@
+//! プロパティの値のエスケープ処理です。
+//!
+//! Propertyの値は、ファイル中の[]の中身をエスケープされたまま保持しています。
+//! Text型の値として読み書きする場合はこのモジュールの関数を使います。
+
+/// Text型の値のエスケープを解除します。
+///
+/// "\\"に続く改行(ソフト改行)は取り除き、"\\"に続く文字はその文字自体にします。
+/// 改行以外の空白文字は空白に置き換えます。
+pub fn unescape(raw: &str) -> String {
+    let mut result = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ '\n') | Some(c @ '\r') => {
+                    // \r\nと\n\rも1つのソフト改行です。
+                    let pair = if c == '\n' { '\r' } else { '\n' };
+                    if chars.peek() == Some(&pair) {
+                        chars.next();
+                    }
+                },
+                Some(c) => result.push(c),
+                None => {},
+            },
+            '\n' | '\r' => result.push(c),
+            c if c.is_whitespace() => result.push(' '),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// 文字列をText型の値としてエスケープします。
+/// "]", "\\", ":"の前に"\\"を付けます。
+pub fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == ']' || c == '\\' || c == ':' {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Compose型の値をエスケープされていない最初の":"で2つに分けます。
+/// ":"がなければNoneを返します。
+pub fn split_compose(raw: &str) -> Option<(&str, &str)> {
+    let mut escaped = false;
+    for (i, c) in raw.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == ':' {
+            return Some((&raw[..i], &raw[i + 1..]));
+        }
+    }
+    None
+}
@@ -0,0 +1,477 @@
+//! GTP(Go Text Protocol) version 2のエンジン側の実装です。
+//!
+//! 標準入力からコマンドを読み、標準出力に応答を書きます。
+//! SabakiやGoGui, twogtpなどのGTPクライアントから利用できます。
+
+use std::io;
+use std::io::{BufRead, Write};
+use rand::{thread_rng, Rng};
+use go_board::*;
+use go_rule::rule::*;
+use go_rule::position::*;
+use go_rule::game::*;
+use go_rule::result::*;
+use go_rule::ruleset::*;
+use go_rule::handicap::*;
+use go_rule::semeai::*;
+
+/// コマンドの応答です。Okが成功(=)、Errが失敗(?)のメッセージです。
+type Response = Result<String, String>;
+
+/// 対応しているコマンドの一覧です。
+static KNOWN_COMMANDS: [&str; 19] = [
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "quit",
+    "boardsize",
+    "clear_board",
+    "komi",
+    "play",
+    "genmove",
+    "undo",
+    "showboard",
+    "final_score",
+    "kgs-rules",
+    "fixed_handicap",
+    "place_free_handicap",
+    "set_free_handicap",
+    "kiri-semeai",
+];
+
+/// 盤サイズごとのエンジンを同じように扱うためのトレイトです。
+/// 盤サイズに依存しないコマンドはEngineが処理し、それ以外をこのトレイトに委ねます。
+trait Commands {
+    /// コマンドを1つ実行します。
+    fn execute(&mut self, command: &str, args: &[&str]) -> Response;
+
+    /// コミを返します。
+    fn get_komi(&self) -> f32;
+
+    /// コミを設定します。
+    fn set_komi(&mut self, komi: f32);
+
+    /// ルールを返します。
+    fn get_rule_set(&self) -> RuleSet;
+
+    /// ルールを設定します。
+    fn set_rule_set(&mut self, rule_set: RuleSet);
+}
+
+/// 盤サイズsizeのCommandsを返します。対応していないサイズならNoneを返します。
+fn new_commands(size: LinearCoord) -> Option<Box<dyn Commands>> {
+    match size {
+        9  => Some(Box::new(SizedEngine::<Position9>::new())),
+        13 => Some(Box::new(SizedEngine::<Position13>::new())),
+        19 => Some(Box::new(SizedEngine::<Position19>::new())),
+        21 => Some(Box::new(SizedEngine::<Position21>::new())),
+        23 => Some(Box::new(SizedEngine::<Position23>::new())),
+        25 => Some(Box::new(SizedEngine::<Position25>::new())),
+        _  => None,
+    }
+}
+
+/// GTPエンジンの状態を保持する構造体です。
+pub struct Engine {
+    /// 現在の盤サイズのエンジン
+    commands: Box<dyn Commands>,
+    /// quitを受け取ったか
+    quit: bool,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Engine {
+            commands: new_commands(19).unwrap(),
+            quit: false,
+        }
+    }
+
+    /// inputからコマンドを読み、outputに応答を書きます。
+    /// quitを受け取るか、inputが終わるまで続けます。
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()> {
+        for line in input.lines() {
+            let line = preprocess(&line?);
+            let mut words = line.split_whitespace();
+            let (id, command) = match words.next() {
+                Some(word) if word.chars().all(|c| c.is_ascii_digit()) => (word, words.next()),
+                word => ("", word),
+            };
+            let args: Vec<&str> = words.collect();
+            let response = match command {
+                Some(command) => self.execute(command, &args),
+                None if id.is_empty() => continue,
+                None => Err("missing command".to_string()),
+            };
+            let (prefix, message) = match response {
+                Ok(message) => ('=', message),
+                Err(message) => ('?', message),
+            };
+            if message.is_empty() {
+                write!(output, "{}{}\n\n", prefix, id)?;
+            } else {
+                write!(output, "{}{} {}\n\n", prefix, id, message)?;
+            }
+            output.flush()?;
+            if self.quit {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// コマンドを1つ実行します。
+    fn execute(&mut self, command: &str, args: &[&str]) -> Response {
+        match command {
+            "protocol_version" => Ok("2".to_string()),
+            "name" => Ok("kiri".to_string()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            "known_command" => {
+                let name = args.first().ok_or("syntax error")?;
+                Ok(KNOWN_COMMANDS.contains(name).to_string())
+            },
+            "list_commands" => Ok(KNOWN_COMMANDS.join("\n")),
+            "quit" => {
+                self.quit = true;
+                Ok(String::new())
+            },
+            "boardsize" => {
+                let size = args.first().and_then(|s| s.parse::<LinearCoord>().ok()).ok_or("syntax error")?;
+                let mut commands = new_commands(size).ok_or("unacceptable size")?;
+                commands.set_komi(self.commands.get_komi());
+                commands.set_rule_set(self.commands.get_rule_set());
+                self.commands = commands;
+                Ok(String::new())
+            },
+            _ => self.commands.execute(command, args),
+        }
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::new()
+    }
+}
+
+/// 盤サイズが決まったエンジンの状態を保持する構造体です。
+struct SizedEngine<T: Rule> {
+    /// 対局の着手の木と現在の局面
+    game: Game<T>,
+}
+
+impl<T: Rule + Default> SizedEngine<T> {
+    fn new() -> Self {
+        SizedEngine {
+            game: Game::new(T::default()),
+        }
+    }
+
+    /// 現在のコミとルールの空の局面を返します。
+    fn new_position(&self) -> T {
+        let mut position = T::default();
+        position.set_komi(self.game.position().get_komi());
+        position.set_rule_set(self.game.position().get_rule_set());
+        position
+    }
+
+    fn clear_board(&mut self) {
+        self.game = Game::new(self.new_position());
+    }
+
+    /// 空の盤にpointsの置き石を置いた局面から対局をやり直します。
+    fn set_handicap(&mut self, points: &[LinearCoord]) -> Result<(), String> {
+        let position = self.game.position();
+        if position.all_points().any(|pt| position.get_state(pt).is_stone()) {
+            return Err("board not empty".to_string());
+        }
+        let mut position = self.new_position();
+        position.set_handicap(points).map_err(|_| "bad vertex list")?;
+        self.game = Game::new(position);
+        Ok(())
+    }
+
+    fn fixed_handicap(&mut self, args: &[&str]) -> Response {
+        let num = args.first().and_then(|s| s.parse::<usize>().ok()).ok_or("syntax error")?;
+        let points = fixed_handicap_points(self.game.position(), num).ok_or("invalid number of stones")?;
+        self.set_handicap(&points)?;
+        Ok(self.vertices(&points))
+    }
+
+    fn place_free_handicap(&mut self, args: &[&str]) -> Response {
+        let num = args.first().and_then(|s| s.parse::<usize>().ok()).ok_or("syntax error")?;
+        if num < 2 {
+            return Err("invalid number of stones".to_string());
+        }
+        let points = free_handicap_points(self.game.position(), num);
+        self.set_handicap(&points)?;
+        Ok(self.vertices(&points))
+    }
+
+    fn set_free_handicap(&mut self, args: &[&str]) -> Response {
+        if args.len() < 2 {
+            return Err("bad vertex list".to_string());
+        }
+        let mut points = Vec::new();
+        for arg in args {
+            match self.game.position().algebraic_to_move(arg) {
+                Ok(Move::Linear(pt)) => points.push(pt),
+                _ => return Err("bad vertex list".to_string()),
+            }
+        }
+        if points.len() >= self.game.position().empties().len() {
+            return Err("bad vertex list".to_string());
+        }
+        self.set_handicap(&points)?;
+        Ok(String::new())
+    }
+
+    /// 2つの頂点の石を含む連の攻め合いを分析します。
+    ///
+    /// 内ダメの数、黒と白の外ダメと眼の数、黒と白がそれぞれ先に打ったときの結果(black, white, seki)と最善の着手を返します。
+    fn semeai(&self, args: &[&str]) -> Response {
+        if args.len() < 2 {
+            return Err("syntax error".to_string());
+        }
+        let position = self.game.position();
+        let mut points = Vec::new();
+        for arg in &args[..2] {
+            match position.algebraic_to_move(arg) {
+                Ok(Move::Linear(pt)) => points.push(pt),
+                _ => return Err("invalid vertex".to_string()),
+            }
+        }
+        let semeai = evaluate_semeai(position, points[0], points[1]).ok_or("not a semeai")?;
+        let verdict = |verdict: SemeaiVerdict| {
+            let outcome = match verdict.outcome {
+                SemeaiOutcome::Win(Color::Black) => "black",
+                SemeaiOutcome::Win(Color::White) => "white",
+                SemeaiOutcome::Seki => "seki",
+            };
+            format!("{} {}", outcome, position.str_coord(verdict.key_move))
+        };
+        Ok(format!("shared {}\nblack outside {} eyes {}\nwhite outside {} eyes {}\nblack_first {}\nwhite_first {}",
+                   semeai.shared.len(),
+                   semeai.black.outside.len(), semeai.black.eyes,
+                   semeai.white.outside.len(), semeai.white.eyes,
+                   verdict(semeai.black_first), verdict(semeai.white_first)))
+    }
+
+    /// 線形座標の配列をGTPの頂点のリストに変換します。
+    fn vertices(&self, points: &[LinearCoord]) -> String {
+        points.iter()
+            .map(|&pt| self.game.position().str_coord(Move::Linear(pt)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn play(&mut self, args: &[&str]) -> Response {
+        if args.len() < 2 {
+            return Err("syntax error".to_string());
+        }
+        let color = parse_color(args[0]).ok_or("syntax error")?;
+        let mov = self.game.position().algebraic_to_move(args[1]).map_err(board_error_message)?;
+        self.game.play_as(color, mov).map_err(|_| "illegal move")?;
+        Ok(String::new())
+    }
+
+    fn genmove(&mut self, args: &[&str]) -> Response {
+        let color = args.first().and_then(|s| parse_color(s)).ok_or("syntax error")?;
+        let mov = self.random_play(color);
+        Ok(self.game.position().str_coord(mov))
+    }
+
+    /// 手番colorで自分の眼を潰さない合法手をランダムに選んで着手します。そのような手がなければパスします。
+    fn random_play(&mut self, color: Color) -> Move {
+        let candidates = self.game.position().empties();
+        if !candidates.is_empty() {
+            let start_index = thread_rng().gen_range(0, candidates.len());
+            for i in (start_index..candidates.len()).chain(0..start_index) {
+                let mov = Move::Linear(candidates[i]);
+                if self.game.position().is_eye(candidates[i]) != color.to_pointstate()
+                    && self.game.play_as(color, mov).is_ok() {
+                    return mov;
+                }
+            }
+        }
+        self.game.play_as(color, Move::Pass).expect("pass is always legal");
+        Move::Pass
+    }
+}
+
+impl<T: Rule + Default> Commands for SizedEngine<T> {
+    fn execute(&mut self, command: &str, args: &[&str]) -> Response {
+        match command {
+            "clear_board" => {
+                self.clear_board();
+                Ok(String::new())
+            },
+            "komi" => {
+                let komi = args.first().and_then(|s| s.parse::<f32>().ok()).ok_or("syntax error")?;
+                self.set_komi(komi);
+                Ok(String::new())
+            },
+            "play" => self.play(args),
+            "genmove" => self.genmove(args),
+            "undo" => if self.game.back() {
+                Ok(String::new())
+            } else {
+                Err("cannot undo".to_string())
+            },
+            "showboard" => Ok(format!("\n{}", self.game.position()).trim_end().to_string()),
+            "final_score" => Ok(GameResult::from_score(self.game.position().rule_score(&[])).to_string()),
+            "fixed_handicap" => self.fixed_handicap(args),
+            "place_free_handicap" => self.place_free_handicap(args),
+            "set_free_handicap" => self.set_free_handicap(args),
+            "kiri-semeai" => self.semeai(args),
+            "kgs-rules" => {
+                let rule_set = args.first().and_then(|s| RuleSet::from_name(s)).ok_or("unknown rules")?;
+                self.set_rule_set(rule_set);
+                self.set_komi(rule_set.komi);
+                Ok(String::new())
+            },
+            _ => Err("unknown command".to_string()),
+        }
+    }
+
+    fn get_komi(&self) -> f32 {
+        self.game.position().get_komi()
+    }
+
+    fn set_komi(&mut self, komi: f32) {
+        self.game.set_komi(komi);
+    }
+
+    fn get_rule_set(&self) -> RuleSet {
+        self.game.position().get_rule_set()
+    }
+
+    fn set_rule_set(&mut self, rule_set: RuleSet) {
+        self.game.set_rule_set(rule_set);
+    }
+}
+
+/// GTPの仕様に従ってコマンド行を整形します。
+/// 制御文字を取り除き、タブを空白に置き換え、コメントを削除します。
+fn preprocess(line: &str) -> String {
+    line.chars()
+        .take_while(|&c| c != '#')
+        .filter(|&c| c == '\t' || !c.is_control())
+        .map(|c| if c == '\t' { ' ' } else { c })
+        .collect()
+}
+
+/// GTPの色の表現を解釈します。
+fn parse_color(s: &str) -> Option<Color> {
+    match s.to_lowercase().as_str() {
+        "b" | "black" => Some(Color::Black),
+        "w" | "white" => Some(Color::White),
+        _             => None,
+    }
+}
+
+/// BoardErrorをGTPのエラーメッセージに変換します。
+fn board_error_message(error: BoardError) -> String {
+    match error {
+        BoardError::InvalidVertex => "invalid vertex".to_string(),
+        _ => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(input: &str) -> String {
+        let mut engine = Engine::new();
+        let mut output = Vec::new();
+        engine.run(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_ids_and_comments() {
+        assert_eq!(session("1 protocol_version\n# comment only\n\nname # trailing\n"),
+                   "=1 2\n\n= kiri\n\n");
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        assert_eq!(session("7 foo\n"), "?7 unknown command\n\n");
+    }
+
+    #[test]
+    fn test_boardsize() {
+        assert_eq!(session("boardsize 19\nboardsize 7\nboardsize 27\nboardsize x\n"),
+                   "=\n\n? unacceptable size\n\n? unacceptable size\n\n? syntax error\n\n");
+        assert_eq!(session("boardsize 25\nplay b Z25\nplay w A1\nundo\n"), "=\n\n=\n\n=\n\n=\n\n");
+        let output = session("komi 0.5\nboardsize 9\nplay b J9\nplay w K9\nshowboard\nfinal_score\n");
+        assert!(output.starts_with("=\n\n=\n\n=\n\n? invalid vertex\n\n= \n 9  . . . . . . . . X \n"));
+        assert!(output.ends_with("= B+80.5\n\n"));
+    }
+
+    #[test]
+    fn test_play_and_undo() {
+        assert_eq!(session("play b A2\nplay b B1\nplay w A1\nplay w Z4\nplay w D20\nplay x D4\n"),
+                   "=\n\n=\n\n? illegal move\n\n? invalid vertex\n\n? invalid vertex\n\n? syntax error\n\n");
+        assert_eq!(session("play b D4\nundo\nundo\n"), "=\n\n=\n\n? cannot undo\n\n");
+        assert_eq!(session("play b D4\nplay w D4\nplay b D4\n"), "=\n\n? illegal move\n\n? illegal move\n\n");
+    }
+
+    #[test]
+    fn test_genmove() {
+        let output = session("genmove b\nshowboard\n");
+        assert!(output.starts_with("= "));
+        assert_eq!(output.matches('X').count(), 1);
+    }
+
+    #[test]
+    fn test_final_score() {
+        assert_eq!(session("komi 0.5\nfinal_score\nplay b A1\nfinal_score\n"),
+                   "=\n\n= W+0.5\n\n=\n\n= B+360.5\n\n");
+    }
+
+    #[test]
+    fn test_kgs_rules() {
+        // 日本ルールでは石の数を数えません。ルールを選ぶとコミもそのルールの値になります。
+        assert_eq!(session("kgs-rules japanese\nkomi 0.5\nplay b A1\nfinal_score\nkgs-rules chinese\nfinal_score\n"),
+                   "=\n\n=\n\n=\n\n= B+359.5\n\n=\n\n= B+353.5\n\n");
+        assert_eq!(session("kgs-rules foo\n"), "? unknown rules\n\n");
+        assert_eq!(session("kgs-rules aga\nfinal_score\n"), "=\n\n= W+7.5\n\n");
+        // boardsizeとclear_boardの後もルールは変わりません。
+        let mut engine = Engine::new();
+        let mut output = Vec::new();
+        engine.run("kgs-rules new_zealand\nboardsize 9\nclear_board\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(engine.commands.get_rule_set(), RuleSet::new_zealand());
+    }
+
+    #[test]
+    fn test_handicap() {
+        assert_eq!(session("fixed_handicap 4\nplay w D4\n"), "= D4 Q16 D16 Q4\n\n? illegal move\n\n");
+        assert_eq!(session("boardsize 9\nfixed_handicap 3\nfixed_handicap 2\nclear_board\nfixed_handicap 10\n"),
+                   "=\n\n= C3 G7 C7\n\n? board not empty\n\n=\n\n? invalid number of stones\n\n");
+        assert_eq!(session("boardsize 21\nfixed_handicap 2\nplace_free_handicap 1\n"),
+                   "=\n\n? invalid number of stones\n\n? invalid number of stones\n\n");
+        assert_eq!(session("place_free_handicap 2\n"), "= D4 Q16\n\n");
+        assert_eq!(session("set_free_handicap A1 T19\nplay w A1\nplay w B1\n"),
+                   "=\n\n? illegal move\n\n=\n\n");
+        assert_eq!(session("set_free_handicap A1\nset_free_handicap A1 A1\nset_free_handicap A1 pass\nset_free_handicap A1 Z9\n"),
+                   "? bad vertex list\n\n? bad vertex list\n\n? bad vertex list\n\n? bad vertex list\n\n");
+    }
+
+    #[test]
+    fn test_semeai() {
+        assert_eq!(session("boardsize 9\nplay b A9\nplay w B9\nplay b A8\nplay w B8\nplay b C9\nplay b C8\nkiri-semeai A9 B8\n"),
+                   "=\n\n=\n\n=\n\n=\n\n=\n\n=\n\n=\n\n\
+                    = shared 0\nblack outside 1 eyes 0\nwhite outside 1 eyes 0\nblack_first black B7\nwhite_first white A7\n\n");
+        assert_eq!(session("play b A1\nplay w C3\nkiri-semeai A1 C3\nkiri-semeai A1 B1\nkiri-semeai A1\nkiri-semeai A1 pass\n"),
+                   "=\n\n=\n\n? not a semeai\n\n? not a semeai\n\n? syntax error\n\n? invalid vertex\n\n");
+    }
+
+    #[test]
+    fn test_quit() {
+        assert_eq!(session("quit\nname\n"), "=\n\n");
+    }
+}
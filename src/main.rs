@@ -1,3 +1,13 @@
+extern crate rand;
+extern crate go_board;
+extern crate go_rule;
+
+mod gtp;
+
+use std::io;
+
 fn main() {
-    println!("Hello, world!");
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    gtp::Engine::new().run(stdin.lock(), &mut stdout.lock()).expect("I/O error");
 }